@@ -0,0 +1,52 @@
+use super::RecordPredicates;
+use crate::cli::{BamOutput, MixedInputBam, OverlapPredicates};
+use anyhow::{bail, Result};
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone)]
+/// Tag each BAM record with the names of the BED intervals it overlaps
+///
+/// Every record is written to the output. Records overlapping at least one
+/// named interval (BED4, BED6, or BED12) carry the interval names in a string aux tag.
+pub struct AnnotateArgs {
+    #[clap(flatten)]
+    pub inputs: MixedInputBam,
+
+    #[clap(flatten)]
+    pub params: AnnotateParams,
+
+    #[clap(flatten)]
+    pub output: BamOutput,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Parameters")]
+pub struct AnnotateParams {
+    /// Two character aux tag to store the overlapping interval names in
+    ///
+    /// Any existing value of the tag is replaced on annotated records
+    #[clap(short = 'T', long, default_value = "XF", value_parser = parse_aux_tag)]
+    pub tag: String,
+
+    /// Delimiter used to join the names of multiple overlapping intervals
+    #[clap(short, long, default_value = ",")]
+    pub delimiter: String,
+
+    #[clap(flatten)]
+    pub overlap_predicates: OverlapPredicates,
+
+    // Records that do not meet the record predicates are written without a tag
+    #[clap(flatten)]
+    pub record_predicates: RecordPredicates,
+}
+
+fn parse_aux_tag(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    if bytes.len() != 2 || !bytes[0].is_ascii_alphabetic() || !bytes[1].is_ascii_alphanumeric() {
+        bail!(
+            "Invalid aux tag: {} (expected two characters, e.g. XF)",
+            value
+        );
+    }
+    Ok(value.to_string())
+}
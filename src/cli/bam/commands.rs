@@ -1,14 +1,28 @@
-use super::{BamCoverageArgs, ConvertArgs, FilterArgs};
+use super::{
+    AnnotateArgs, BamCoverageArgs, ConvertArgs, CountArgs, FilterArgs, GenomecovArgs, StatsArgs,
+};
 use clap::Parser;
 
 #[derive(Parser, Debug, Clone)]
 pub enum BamCommand {
+    /// Tag BAM records with the names of overlapping interval regions
+    Annotate(AnnotateArgs),
+
     /// Convert BAM to different formats
     Convert(ConvertArgs),
 
+    /// Count BAM records assigned to genes of a GTF file
+    Count(CountArgs),
+
     /// Measure coverage of BAM records over interval regions
     Coverage(BamCoverageArgs),
 
     /// Filter BAM records based on overlap criteria to other regions
     Filter(FilterArgs),
+
+    /// Report the per-base depth of BAM records as a bedGraph
+    Genomecov(GenomecovArgs),
+
+    /// Report quality control statistics of BAM records per region
+    Stats(StatsArgs),
 }
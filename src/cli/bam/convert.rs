@@ -1,4 +1,6 @@
+use super::RecordPredicates;
 use crate::cli::SingleInputBam;
+use anyhow::{bail, Result};
 use clap::{Parser, ValueEnum};
 
 #[derive(Parser, Debug, Clone)]
@@ -20,6 +22,9 @@ pub struct ConvertParams {
     #[clap(short, long, default_value = "bed")]
     pub conv: BamConversionType,
 
+    #[clap(flatten)]
+    pub record_predicates: RecordPredicates,
+
     #[clap(flatten)]
     pub bed: BedConversionParams,
 
@@ -33,15 +38,104 @@ pub struct BedConversionParams {
     #[clap(short = 'C', long)]
     /// Include CIGAR string in BED output
     pub cigar: bool,
+
+    /// Minimum deletion length that splits an aligned block in BED12 output
+    ///
+    /// Reference skips (N) always split blocks, deletions shorter than this
+    /// are kept within their surrounding block (default: never split on deletions)
+    #[clap(long)]
+    pub split_deletions: Option<usize>,
+
+    /// Aux tags (e.g. CB,UB,NM) or core fields to append as columns
+    ///
+    /// Core fields are flag, tlen, mchr (mate chromosome), and mpos (0-based mate
+    /// position). Columns are appended in the order given after all other columns.
+    #[clap(short = 'T', long, value_delimiter = ',', value_parser = parse_extra_column)]
+    pub columns: Vec<ExtraColumn>,
+
+    /// Placeholder written for aux tags and mate fields missing from a record
+    #[clap(long, default_value = ".")]
+    pub missing: String,
+}
+
+/// A column appended to BED output from a record field
+#[derive(Debug, Clone, Copy)]
+pub enum ExtraColumn {
+    /// A two character SAM aux tag
+    Tag([u8; 2]),
+    Flag,
+    Tlen,
+    MateChr,
+    MatePos,
+}
+
+fn parse_extra_column(value: &str) -> Result<ExtraColumn> {
+    match value {
+        "flag" => Ok(ExtraColumn::Flag),
+        "tlen" => Ok(ExtraColumn::Tlen),
+        "mchr" => Ok(ExtraColumn::MateChr),
+        "mpos" => Ok(ExtraColumn::MatePos),
+        tag => match tag.as_bytes() {
+            &[a, b] if a.is_ascii_alphabetic() && b.is_ascii_alphanumeric() => {
+                Ok(ExtraColumn::Tag([a, b]))
+            }
+            _ => bail!(
+                "Invalid column: {} (expected an aux tag or one of flag, tlen, mchr, mpos)",
+                value
+            ),
+        },
+    }
 }
 
 #[derive(Parser, Debug, Clone)]
 #[clap(next_help_heading = "FASTQ Conversion Options")]
-pub struct FastqConversionParams {}
+pub struct FastqConversionParams {
+    /// Output FASTQ file for the first mate of paired reads (default=stdout)
+    ///
+    /// Mates are matched by name and written in the same order to R1 and R2.
+    /// If the file ends with .gz or .bgz it will be BGZF compressed
+    #[clap(long)]
+    pub r1: Option<String>,
+
+    /// Output FASTQ file for the second mate of paired reads
+    ///
+    /// If not provided, mates are interleaved in the R1 output
+    #[clap(long)]
+    pub r2: Option<String>,
+
+    /// Output FASTQ file for unpaired reads and reads whose mate is missing
+    ///
+    /// If not provided, these reads are written to R1 when mates are
+    /// interleaved and dropped when R2 is provided
+    #[clap(long)]
+    pub singletons: Option<String>,
+
+    /// Include secondary alignments (written as singletons)
+    #[clap(long)]
+    pub include_secondary: bool,
+
+    /// Include supplementary alignments (written as singletons)
+    #[clap(long)]
+    pub include_supplementary: bool,
+
+    /// Compression threads to use for FASTQ output files if applicable
+    #[clap(short = 'j', long, default_value = "1")]
+    pub compression_threads: usize,
+
+    /// Compression level to use for FASTQ output files if applicable
+    #[clap(long, default_value = "6")]
+    pub compression_level: u32,
+}
 
 #[derive(Debug, Clone, ValueEnum, Default)]
 pub enum BamConversionType {
     #[default]
     Bed,
+    /// Spliced BED12 with one block per aligned segment
+    Bed12,
+    /// Insert span of properly paired mates as BED3 (unsorted, written once both mates are read)
+    Fragment,
+    /// Properly paired mates as BEDPE (unsorted, written once both mates are read)
+    Bedpe,
     Fastq,
 }
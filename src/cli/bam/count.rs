@@ -0,0 +1,73 @@
+use super::RecordPredicates;
+use crate::cli::{gtf::GtfSelection, overlap_predicates::WrapStrandedness, MixedInputGtf, Output};
+use clap::{Parser, ValueEnum};
+
+#[derive(Parser, Debug, Clone)]
+/// Count the BAM records (or read pairs) assigned to each gene of a GTF file
+pub struct CountArgs {
+    #[clap(flatten)]
+    pub inputs: MixedInputGtf,
+
+    #[clap(flatten)]
+    pub params: CountParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+// Genes are counted over their exons and named by their `gene_id` unless other
+// features or names are selected
+#[derive(Parser, Debug, Clone)]
+#[clap(
+    next_help_heading = "Parameters",
+    mut_arg("feature", |arg| arg.default_value("exon")),
+)]
+pub struct CountParams {
+    /// Number of threads to use when reading BAM file
+    #[clap(short, long, default_value = "1")]
+    pub threads: usize,
+
+    /// How to resolve reads overlapping multiple genes
+    ///
+    /// union: assign if the union of overlapped genes is a single gene
+    ///
+    /// intersection-strict: assign if a single gene covers every aligned base
+    #[clap(short, long, default_value = "union")]
+    pub mode: CountMode,
+
+    /// Strand-specificity of the library relative to the gene
+    ///
+    /// i: Ignore strand (default)
+    ///
+    /// m: Match strand (+/+ or -/- only)
+    ///
+    /// o: Opposite strand (+/- or -/+ only)
+    #[clap(short = 's', long, default_value = "i")]
+    pub strandedness: WrapStrandedness,
+
+    /// Count multi-mapping reads (NH > 1 or secondary) at every alignment
+    ///
+    /// Otherwise they are reported as unassigned multi-mapping
+    #[clap(short = 'M', long)]
+    pub multi_mapping: bool,
+
+    /// Count read pairs as a single fragment instead of counting each mate
+    #[clap(short, long)]
+    pub paired: bool,
+
+    /// Write a summary of assigned and unassigned reads to this file
+    #[clap(long)]
+    pub summary: Option<String>,
+
+    #[clap(flatten)]
+    pub selection: GtfSelection,
+
+    #[clap(flatten)]
+    pub record_predicates: RecordPredicates,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CountMode {
+    Union,
+    IntersectionStrict,
+}
@@ -1,3 +1,4 @@
+use super::RecordPredicates;
 use crate::cli::{MixedInputBam, Output, OverlapPredicates};
 use clap::Parser;
 
@@ -27,6 +28,25 @@ pub struct BamCoverageParams {
     #[clap(short, long, default_value = "1")]
     pub threads: usize,
 
+    /// Report the number of overlapping records on the same strand (sense) and the opposite
+    /// strand (antisense) of each interval
+    ///
+    /// Intervals without a strand report zero for both columns
+    #[clap(long)]
+    pub strand_counts: bool,
+
+    /// Report the number of bases covered, the length of the interval, and the fraction of the
+    /// interval covered by at least one record
+    #[clap(long)]
+    pub breadth: bool,
+
+    /// Report the mean depth across all bases of each interval
+    #[clap(long)]
+    pub mean_depth: bool,
+
     #[clap(flatten)]
     pub overlap_predicates: OverlapPredicates,
+
+    #[clap(flatten)]
+    pub record_predicates: RecordPredicates,
 }
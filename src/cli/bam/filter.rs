@@ -1,3 +1,4 @@
+use super::RecordPredicates;
 use crate::cli::{BamOutput, MixedInputBam, OverlapPredicates};
 
 use clap::Parser;
@@ -20,6 +21,9 @@ pub struct FilterParams {
     #[clap(flatten)]
     pub overlap_predicates: OverlapPredicates,
 
+    #[clap(flatten)]
+    pub record_predicates: RecordPredicates,
+
     #[clap(flatten)]
     pub output_predicates: OutputPredicates,
 }
@@ -30,4 +34,19 @@ pub struct OutputPredicates {
     /// Only return the records from a that DON'T overlap with b
     #[clap(short = 'v', long)]
     pub invert: bool,
+
+    /// Write the records that are not reported to this file
+    ///
+    /// Uses the same output format and number of threads as the main output
+    #[clap(short = 'u', long)]
+    pub complement: Option<String>,
+
+    /// Write the records overlapping each BED name to a separate file named
+    /// `<PREFIX><name>.<format>` instead of the main output
+    ///
+    /// Requires named intervals (BED4, BED6, or BED12). Records overlapping
+    /// intervals with different names are written to each of their files and
+    /// path separators in names are replaced with underscores.
+    #[clap(long, value_name = "PREFIX", conflicts_with = "invert")]
+    pub split: Option<String>,
 }
@@ -0,0 +1,56 @@
+use super::RecordPredicates;
+use crate::cli::{Output, SingleInputBam};
+use clap::{Parser, ValueEnum};
+
+#[derive(Parser, Debug, Clone)]
+/// Calculate the per-base depth of a coordinate-sorted BAM file and report it as a bedGraph
+pub struct GenomecovArgs {
+    #[clap(flatten)]
+    pub input: SingleInputBam,
+
+    #[clap(flatten)]
+    pub params: GenomecovParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Parameters")]
+pub struct GenomecovParams {
+    /// Number of threads to use when reading BAM file
+    #[clap(short, long, default_value = "1")]
+    pub threads: usize,
+
+    /// Split reads on reference skips (N) and deletions (D) so that
+    /// only aligned blocks contribute to the depth
+    #[clap(long)]
+    pub split: bool,
+
+    /// Only count reads aligned to a specific strand
+    #[clap(long)]
+    pub strand: Option<GenomecovStrand>,
+
+    /// Scale the depth to counts per million of the reads counted
+    #[clap(long, conflicts_with = "scale")]
+    pub cpm: bool,
+
+    /// Scale the depth by a constant factor
+    #[clap(long)]
+    pub scale: Option<f64>,
+
+    /// Report regions with zero coverage
+    #[clap(short = 'z', long)]
+    pub zero: bool,
+
+    #[clap(flatten)]
+    pub record_predicates: RecordPredicates,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum GenomecovStrand {
+    /// Only count reads on the forward strand
+    Forward,
+    /// Only count reads on the reverse strand
+    Reverse,
+}
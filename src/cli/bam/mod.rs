@@ -1,9 +1,21 @@
+mod annotate;
 mod commands;
 mod convert;
+mod count;
 mod coverage;
 mod filter;
+mod genomecov;
+mod predicates;
+mod stats;
 
+pub use annotate::{AnnotateArgs, AnnotateParams};
 pub use commands::BamCommand;
-pub use convert::{BamConversionType, ConvertArgs, ConvertParams};
+pub use convert::{
+    BamConversionType, ConvertArgs, ConvertParams, ExtraColumn, FastqConversionParams,
+};
+pub use count::{CountArgs, CountMode, CountParams};
 pub use coverage::{BamCoverageArgs, BamCoverageParams};
 pub use filter::{FilterArgs, FilterParams};
+pub use genomecov::{GenomecovArgs, GenomecovParams, GenomecovStrand};
+pub use predicates::RecordPredicates;
+pub use stats::{StatsArgs, StatsParams};
@@ -0,0 +1,45 @@
+use anyhow::{bail, Result};
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone, Copy)]
+#[clap(next_help_heading = "Record Predicates")]
+pub struct RecordPredicates {
+    /// Minimum mapping quality of BAM records to consider
+    #[clap(short = 'q', long, default_value = "0")]
+    pub min_mapq: u8,
+
+    /// Only consider BAM records with all of these SAM flag bits set
+    ///
+    /// Accepts either a decimal (e.g. 2) or hexadecimal (e.g. 0x2) mask
+    #[clap(long, default_value = "0", value_parser = parse_flag_mask)]
+    pub require_flags: u16,
+
+    /// Skip BAM records with any of these SAM flag bits set
+    ///
+    /// Accepts either a decimal (e.g. 3844) or hexadecimal (e.g. 0xF04) mask
+    #[clap(long, default_value = "0", value_parser = parse_flag_mask)]
+    pub exclude_flags: u16,
+
+    /// Only consider BAM records that are mapped in a proper pair
+    #[clap(long)]
+    pub proper_pair: bool,
+
+    /// Minimum number of aligned bases (M/=/X) of BAM records to consider
+    #[clap(long, default_value = "0")]
+    pub min_aligned_length: usize,
+}
+
+fn parse_flag_mask(value: &str) -> Result<u16> {
+    let mask = if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        u16::from_str_radix(hex, 16)
+    } else {
+        value.parse::<u16>()
+    };
+    match mask {
+        Ok(mask) => Ok(mask),
+        Err(_) => bail!("Invalid SAM flag mask: {}", value),
+    }
+}
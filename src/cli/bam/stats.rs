@@ -0,0 +1,43 @@
+use super::RecordPredicates;
+use crate::cli::{Output, SingleInputBam};
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone)]
+/// Report quality control statistics of a BAM file for each BED interval (or genome-wide)
+///
+/// Each row reports the interval followed by the number of reads, the number of
+/// proper pairs, the mean and median insert size, the duplicate fraction, the mean
+/// depth, the coefficient of variation of the depth, and a MAPQ histogram with the
+/// bins 0, 1-9, 10-19, 20-29, 30-39, 40-59, 60-254, and 255 (unavailable).
+pub struct StatsArgs {
+    #[clap(flatten)]
+    pub input: SingleInputBam,
+
+    #[clap(flatten)]
+    pub params: StatsParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Parameters")]
+pub struct StatsParams {
+    /// BED file of regions to report statistics for
+    ///
+    /// If not provided a single genome-wide row is reported, which requires
+    /// a coordinate-sorted BAM file.
+    #[clap(short, long)]
+    pub bed: Option<String>,
+
+    /// Also write the statistics as JSON to this file
+    #[clap(long)]
+    pub json: Option<String>,
+
+    /// Number of threads to use when reading BAM file
+    #[clap(short, long, default_value = "1")]
+    pub threads: usize,
+
+    #[clap(flatten)]
+    pub record_predicates: RecordPredicates,
+}
@@ -0,0 +1,50 @@
+use super::SpanPolicy;
+use crate::cli::{outputs::VcfOutput, MixedInputVcf, OverlapPredicates};
+use clap::{Parser, ValueEnum};
+
+#[derive(Parser, Debug, Clone)]
+/// Annotate BCF/VCF records with an INFO key from the overlapping BED intervals
+///
+/// Every record is written back out. Records overlapping at least one interval
+/// receive the INFO key, and a matching `##INFO` header line is added.
+pub struct AnnotateArgs {
+    #[clap(flatten)]
+    pub inputs: MixedInputVcf,
+
+    #[clap(flatten)]
+    pub params: AnnotateParams,
+
+    #[clap(flatten)]
+    pub output: VcfOutput,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Parameters")]
+pub struct AnnotateParams {
+    /// INFO key to store the annotation in
+    #[clap(short, long, default_value = "BED")]
+    pub key: String,
+
+    /// Value stored in the INFO key
+    ///
+    /// name: Names of the overlapping intervals (BED4, BED6, or BED12)
+    ///
+    /// score: Scores of the overlapping intervals (BED6, BED12, or bedGraph)
+    ///
+    /// flag: Whether any interval overlaps
+    #[clap(short, long, default_value = "name")]
+    pub mode: AnnotateMode,
+
+    #[clap(flatten)]
+    pub overlap_predicates: OverlapPredicates,
+
+    #[clap(flatten)]
+    pub span_policy: SpanPolicy,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum AnnotateMode {
+    Name,
+    Score,
+    Flag,
+}
@@ -1,8 +1,20 @@
-use super::FilterArgs;
+use super::{AnnotateArgs, BcfCoverageArgs, BcfIntersectArgs, ConvertArgs, FilterArgs};
 use clap::Parser;
 
 #[derive(Parser, Debug, Clone)]
 pub enum BcfCommand {
+    /// Add an INFO key to BCF records from overlapping interval regions
+    Annotate(AnnotateArgs),
+
+    /// Convert BCF records to BED intervals
+    Convert(ConvertArgs),
+
+    /// Count BCF records over interval regions
+    Coverage(BcfCoverageArgs),
+
     /// Filter BCF records based on overlap criteria to other regions
     Filter(FilterArgs),
+
+    /// Match BCF records to the records of another BCF file
+    Intersect(BcfIntersectArgs),
 }
@@ -0,0 +1,48 @@
+use super::SpanPolicy;
+use crate::cli::{Output, SingleInputVcf};
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone)]
+/// Convert BCF/VCF records to BED intervals
+///
+/// Each record is reported as its chromosome, 0-based start, end, name, REF, and
+/// ALT alleles. The end covers the REF allele, or for symbolic structural variants
+/// (e.g. `<DEL>`) is taken from INFO/END or INFO/SVLEN. The name is the record ID,
+/// or `CHROM:POS:REF:ALT` if the record has no ID. Breakends (BND) are reported
+/// once for their anchor and once for each mate.
+pub struct ConvertArgs {
+    #[clap(flatten)]
+    pub input: SingleInputVcf,
+
+    #[clap(flatten)]
+    pub params: ConvertParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Parameters")]
+pub struct ConvertParams {
+    /// INFO keys (e.g. AF,SVTYPE) to append as columns
+    ///
+    /// Columns are appended in the order given after the ALT column.
+    /// Flags are reported as 1 if set and multiple values are comma-separated.
+    #[clap(short = 'I', long, value_delimiter = ',')]
+    pub info: Vec<String>,
+
+    /// Append the genotype of every sample as columns
+    #[clap(short, long, conflicts_with = "samples")]
+    pub genotypes: bool,
+
+    /// Append the genotypes of only these samples as columns (in the order given)
+    #[clap(short, long, value_delimiter = ',')]
+    pub samples: Vec<String>,
+
+    /// Placeholder written for INFO keys and genotypes missing from a record
+    #[clap(long, default_value = ".")]
+    pub missing: String,
+
+    #[clap(flatten)]
+    pub span_policy: SpanPolicy,
+}
@@ -0,0 +1,39 @@
+use super::SpanPolicy;
+use crate::cli::{MixedInputVcf, Output, OverlapPredicates};
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone)]
+/// Count the BCF/VCF records overlapping each BED interval
+pub struct BcfCoverageArgs {
+    #[clap(flatten)]
+    pub inputs: MixedInputVcf,
+
+    #[clap(flatten)]
+    pub params: BcfCoverageParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Parameters")]
+pub struct BcfCoverageParams {
+    /// Report the number of overlapping SNVs, indels, and structural variants of each interval
+    ///
+    /// Records with symbolic or breakend ALT alleles or an INFO/SVTYPE are structural
+    /// variants, records with ALT alleles of a different length than REF are indels,
+    /// and all other records (including MNVs) are SNVs.
+    #[clap(long)]
+    pub by_type: bool,
+
+    /// Report the number of overlapping records where this sample does and does not carry
+    /// a non-reference genotype
+    #[clap(long)]
+    pub sample: Option<String>,
+
+    #[clap(flatten)]
+    pub overlap_predicates: OverlapPredicates,
+
+    #[clap(flatten)]
+    pub span_policy: SpanPolicy,
+}
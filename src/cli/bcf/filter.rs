@@ -1,3 +1,4 @@
+use super::SpanPolicy;
 use crate::cli::{outputs::VcfOutput, MixedInputVcf, OverlapPredicates};
 
 use clap::Parser;
@@ -17,13 +18,43 @@ pub struct FilterArgs {
 #[derive(Parser, Debug, Clone)]
 #[clap(next_help_heading = "Parameters")]
 pub struct FilterParams {
+    /// Stream every record even if the BCF/VCF file is indexed
+    ///
+    /// Indexed files are already streamed when breakend mates, SVLEN-only spans,
+    /// or padded confidence intervals could reach into a region from outside of
+    /// it (i.e. the header declares SVTYPE, MATEID, SVLEN, or CIPOS/CIEND keys
+    /// used by the span options).
+    #[clap(long)]
+    pub no_index: bool,
+
     #[clap(flatten)]
     pub overlap_predicates: OverlapPredicates,
 
+    #[clap(flatten)]
+    pub span_policy: SpanPolicy,
+
+    #[clap(flatten)]
+    pub sample_predicates: SamplePredicates,
+
     #[clap(flatten)]
     pub output_predicates: OutputPredicates,
 }
 
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Sample Predicates")]
+pub struct SamplePredicates {
+    /// Only write the genotypes of these samples and subset the header to them
+    ///
+    /// INFO fields summarizing all samples (e.g. AC and AN) are not recalculated.
+    #[clap(long, value_delimiter = ',')]
+    pub samples: Vec<String>,
+
+    /// Only keep records where at least this many of the selected samples (or all
+    /// samples if none are selected) carry a non-reference genotype
+    #[clap(long)]
+    pub min_carriers: Option<usize>,
+}
+
 #[derive(Parser, Debug, Clone)]
 #[clap(next_help_heading = "Output Predicates")]
 pub struct OutputPredicates {
@@ -0,0 +1,64 @@
+use super::SpanPolicy;
+use crate::cli::{outputs::VcfOutput, DualInputVcf, OverlapPredicates};
+use clap::{Parser, ValueEnum};
+
+#[derive(Parser, Debug, Clone)]
+/// Match the records of two BCF/VCF files
+///
+/// Records are matched by their alleles, their positions, or the overlap of their
+/// spans. The records of b are loaded into memory and the records of a are streamed.
+pub struct BcfIntersectArgs {
+    #[clap(flatten)]
+    pub inputs: DualInputVcf,
+
+    #[clap(flatten)]
+    pub params: BcfIntersectParams,
+
+    #[clap(flatten)]
+    pub output: VcfOutput,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Parameters")]
+pub struct BcfIntersectParams {
+    /// How two records are matched
+    ///
+    /// allele: Same CHROM, POS, REF, and at least one ALT allele (no normalization)
+    ///
+    /// position: Same CHROM and POS
+    ///
+    /// overlap: Overlapping spans, using the overlap predicates and span policy (e.g.
+    /// `-f 0.5 -r` for a 50% reciprocal overlap of SVs)
+    #[clap(short, long, default_value = "allele")]
+    pub mode: MatchMode,
+
+    /// Records to write
+    ///
+    /// shared: Records of a with a match in b
+    ///
+    /// a-only: Records of a without a match in b
+    ///
+    /// b-only: Records of b without a match in a
+    #[clap(short = 'R', long, default_value = "shared")]
+    pub report: IntersectReport,
+
+    #[clap(flatten)]
+    pub overlap_predicates: OverlapPredicates,
+
+    #[clap(flatten)]
+    pub span_policy: SpanPolicy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MatchMode {
+    Allele,
+    Position,
+    Overlap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IntersectReport {
+    Shared,
+    AOnly,
+    BOnly,
+}
@@ -1,5 +1,15 @@
+mod annotate;
 mod commands;
+mod convert;
+mod coverage;
 mod filter;
+mod intersect;
+mod span;
 
+pub use annotate::{AnnotateArgs, AnnotateMode, AnnotateParams};
 pub use commands::BcfCommand;
-pub use filter::{FilterArgs, FilterParams};
+pub use convert::{ConvertArgs, ConvertParams};
+pub use coverage::{BcfCoverageArgs, BcfCoverageParams};
+pub use filter::{FilterArgs, FilterParams, SamplePredicates};
+pub use intersect::{BcfIntersectArgs, IntersectReport, MatchMode};
+pub use span::SpanPolicy;
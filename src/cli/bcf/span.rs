@@ -0,0 +1,19 @@
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone, Copy)]
+#[clap(next_help_heading = "Variant Span Options")]
+pub struct SpanPolicy {
+    /// Pad variant spans by their confidence intervals
+    ///
+    /// The start is extended by the lower bound of INFO/CIPOS and the end by the
+    /// upper bound of INFO/CIEND (or INFO/CIPOS if there is no INFO/CIEND).
+    #[clap(long)]
+    pub ci_padding: bool,
+
+    /// Only use the anchor position of breakends (BND) and ignore their mates
+    ///
+    /// By default the mate position encoded in the ALT allele is used as a
+    /// second point interval of the record.
+    #[clap(long)]
+    pub ignore_mates: bool,
+}
@@ -1,8 +1,8 @@
 use super::{
-    bam::BamCommand, bcf::BcfCommand, ClosestArgs, ClusterArgs, ComplementArgs, CoverageArgs,
-    ExtendArgs, FlankArgs, GetFastaArgs, IntersectArgs, JoinArgs, MergeArgs, RandomArgs,
-    SampleArgs, SegmentArgs, ShiftArgs, SortArgs, SpacingArgs, SubtractArgs, UnionBedGraphArgs,
-    WindowArgs,
+    bam::BamCommand, bcf::BcfCommand, gff::GffCommand, gtf::GtfCommand, peak::PeakCommand,
+    ClosestArgs, ClusterArgs, ComplementArgs, CoverageArgs, ExtendArgs, FlankArgs, GetFastaArgs,
+    IntersectArgs, JoinArgs, MergeArgs, RandomArgs, SampleArgs, SegmentArgs, ShiftArgs, SortArgs,
+    SpacingArgs, SubtractArgs, UnionBedGraphArgs, WindowArgs,
 };
 use clap::Subcommand;
 
@@ -47,6 +47,14 @@ pub enum Command {
     /// Extracts FASTA sequences using intervals from a BED file
     GetFasta(GetFastaArgs),
 
+    /// GFF3-centric commands
+    #[clap(subcommand)]
+    Gff(GffCommand),
+
+    /// GTF-centric commands
+    #[clap(subcommand)]
+    Gtf(GtfCommand),
+
     /// Intersects two BED files
     Intersect(IntersectArgs),
 
@@ -56,6 +64,10 @@ pub enum Command {
     /// Merges intervals of a BED file with overlapping regions
     Merge(MergeArgs),
 
+    /// Peak-centric commands (narrowPeak/broadPeak)
+    #[clap(subcommand)]
+    Peak(PeakCommand),
+
     /// Generates a random BED file given some parameterizations
     Random(RandomArgs),
 
@@ -0,0 +1,8 @@
+use super::GffConvertArgs;
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+pub enum GffCommand {
+    /// Convert GFF3 features to BED6 intervals
+    Convert(GffConvertArgs),
+}
@@ -0,0 +1,44 @@
+use crate::cli::{Output, SingleInput};
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+/// Convert GFF3 features to BED6 intervals
+///
+/// Each feature is reported as its seqid, 0-based start, end, name, score, and
+/// strand. Attributes are URL-decoded and features are linked to their parents by
+/// their `ID` and `Parent` attributes, so a name missing from a feature (e.g. an
+/// exon) is taken from its nearest ancestor (e.g. its mRNA or gene).
+pub struct GffConvertArgs {
+    #[clap(flatten)]
+    pub input: SingleInput,
+
+    #[clap(flatten)]
+    pub params: GffConvertParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+#[derive(Parser, Debug)]
+#[clap(next_help_heading = "Parameters")]
+pub struct GffConvertParams {
+    /// Feature types to report (e.g. gene,mRNA,exon,CDS)
+    ///
+    /// All features are reported if unset.
+    #[clap(short, long, value_delimiter = ',')]
+    pub feature: Vec<String>,
+
+    /// Attribute to use as the BED name (e.g. ID, Name, gene_id)
+    #[clap(short, long, default_value = "ID")]
+    pub name: String,
+
+    /// Only take the name from the nearest ancestor of this type (e.g. gene)
+    ///
+    /// A feature of this type is named by its own attribute.
+    #[clap(short, long)]
+    pub ancestor: Option<String>,
+
+    /// Placeholder name for features without the attribute
+    #[clap(long, default_value = ".")]
+    pub missing: String,
+}
@@ -0,0 +1,5 @@
+mod commands;
+mod convert;
+
+pub use commands::GffCommand;
+pub use convert::{GffConvertArgs, GffConvertParams};
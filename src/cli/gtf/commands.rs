@@ -0,0 +1,26 @@
+use super::{GtfFilterArgs, GtfModelArgs, GtfPromotersArgs, GtfUtrsArgs};
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+pub enum GtfCommand {
+    /// Select GTF features by type and attributes
+    Filter(GtfFilterArgs),
+
+    /// Write the introns (gaps between exons) of each transcript as BED6
+    Introns(GtfModelArgs),
+
+    /// Write a strand-aware promoter window of each transcript as BED6
+    Promoters(GtfPromotersArgs),
+
+    /// Write the transcription end site of each transcript as BED6
+    Tes(GtfModelArgs),
+
+    /// Write each transcript with its exons and CDS as BED12
+    Transcripts(GtfModelArgs),
+
+    /// Write the transcription start site of each transcript as BED6
+    Tss(GtfModelArgs),
+
+    /// Write the 5' or 3' UTRs of each coding transcript as BED6
+    Utrs(GtfUtrsArgs),
+}
@@ -0,0 +1,37 @@
+use super::GtfSelection;
+use crate::cli::{Output, SingleInputGtf};
+use clap::{Parser, ValueEnum};
+
+#[derive(Parser, Debug, Clone)]
+/// Select GTF features by their type and attributes
+///
+/// Selected features are written unchanged, or demoted to BED4/BED6 intervals
+/// (0-based, half-open) named by one of their attributes.
+pub struct GtfFilterArgs {
+    #[clap(flatten)]
+    pub input: SingleInputGtf,
+
+    #[clap(flatten)]
+    pub selection: GtfSelection,
+
+    #[clap(flatten)]
+    pub params: GtfFilterParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Parameters")]
+pub struct GtfFilterParams {
+    /// Format to write the selected features as
+    #[clap(short = 'O', long, default_value = "gtf")]
+    pub output_format: GtfOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GtfOutputFormat {
+    Gtf,
+    Bed4,
+    Bed6,
+}
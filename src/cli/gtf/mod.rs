@@ -0,0 +1,13 @@
+mod commands;
+mod filter;
+mod model;
+mod promoters;
+mod selection;
+mod utrs;
+
+pub use commands::GtfCommand;
+pub use filter::{GtfFilterArgs, GtfOutputFormat};
+pub use model::{GtfModelArgs, TranscriptModelOptions};
+pub use promoters::GtfPromotersArgs;
+pub use selection::GtfSelection;
+pub use utrs::{GtfUtrsArgs, UtrEnd};
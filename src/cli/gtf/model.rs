@@ -0,0 +1,32 @@
+use super::GtfSelection;
+use crate::cli::{Output, SingleInputGtf};
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone)]
+/// Derive annotation sets from the transcripts of a GTF file
+///
+/// Transcript models are built by grouping the exon and CDS features of the GTF by
+/// their `transcript_id` attribute. Output is written in transcript order of
+/// appearance with 0-based, half-open coordinates.
+pub struct GtfModelArgs {
+    #[clap(flatten)]
+    pub input: SingleInputGtf,
+
+    #[clap(flatten)]
+    pub model: TranscriptModelOptions,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+// Transcripts are built from their exon, CDS, and stop codon features and are
+// named by their `transcript_id` unless other features or names are selected
+#[derive(Parser, Debug, Clone)]
+#[clap(
+    mut_arg("feature", |arg| arg.default_value("exon,CDS,stop_codon")),
+    mut_arg("name", |arg| arg.default_value("transcript_id"))
+)]
+pub struct TranscriptModelOptions {
+    #[clap(flatten)]
+    pub selection: GtfSelection,
+}
@@ -0,0 +1,23 @@
+use super::TranscriptModelOptions;
+use crate::cli::{Growth, Output, SingleInputGtf};
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone)]
+/// Write a promoter window around the TSS of each transcript of a GTF file
+///
+/// The window is grown from the single base TSS with the growth options and
+/// always follows the transcript strand: `--left` is upstream and `--right` is
+/// downstream of the TSS (e.g. `-l 2000 -r 200`).
+pub struct GtfPromotersArgs {
+    #[clap(flatten)]
+    pub input: SingleInputGtf,
+
+    #[clap(flatten)]
+    pub model: TranscriptModelOptions,
+
+    #[clap(flatten)]
+    pub growth: Growth,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
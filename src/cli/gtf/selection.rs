@@ -0,0 +1,81 @@
+use crate::utils::parse_gtf_attribute;
+use anyhow::{bail, Result};
+use clap::Parser;
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "GTF Selection")]
+pub struct GtfSelection {
+    /// Feature types to keep (e.g. exon,CDS)
+    ///
+    /// Every feature type is kept if unset and there is no default.
+    #[clap(short, long, value_delimiter = ',')]
+    pub feature: Vec<String>,
+
+    /// Attribute predicates a feature must satisfy (e.g. `gene_biotype==protein_coding`)
+    ///
+    /// Accepts `key==value`, `key!=value`, or a bare `key` requiring the attribute to be
+    /// set. Features must satisfy every predicate given and a feature missing the
+    /// attribute never equals the value.
+    #[clap(short = 'w', long = "where", value_parser = parse_attribute_predicate)]
+    pub predicates: Vec<AttributePredicate>,
+
+    /// Attribute naming the selected features (e.g. gene_name, transcript_id)
+    ///
+    /// Used as the name when writing BED4/BED6 (`.` if missing), the gene of
+    /// `bam count`, and the name of transcript models.
+    #[clap(short, long, default_value = "gene_id")]
+    pub name: String,
+}
+impl GtfSelection {
+    /// Checks whether a feature is of a selected type and satisfies every predicate
+    pub fn matches(&self, feature: &str, attributes: &str) -> bool {
+        (self.feature.is_empty() || self.feature.iter().any(|f| f == feature))
+            && self.predicates.iter().all(|p| p.matches(attributes))
+    }
+
+    /// Returns the name of a feature from its attributes
+    pub fn get_name<'a>(&self, attributes: &'a str) -> &'a str {
+        parse_gtf_attribute(attributes, &self.name).unwrap_or(".")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributePredicate {
+    Equal(String, String),
+    NotEqual(String, String),
+    Exists(String),
+}
+impl AttributePredicate {
+    pub fn matches(&self, attributes: &str) -> bool {
+        match self {
+            Self::Equal(key, value) => parse_gtf_attribute(attributes, key) == Some(value.as_str()),
+            Self::NotEqual(key, value) => {
+                parse_gtf_attribute(attributes, key) != Some(value.as_str())
+            }
+            Self::Exists(key) => parse_gtf_attribute(attributes, key).is_some(),
+        }
+    }
+}
+
+pub fn parse_attribute_predicate(value: &str) -> Result<AttributePredicate> {
+    let unquote = |v: &str| v.trim().trim_matches('"').to_string();
+    let predicate = if let Some((key, v)) = value.split_once("!=") {
+        AttributePredicate::NotEqual(key.trim().to_string(), unquote(v))
+    } else if let Some((key, v)) = value.split_once("==") {
+        AttributePredicate::Equal(key.trim().to_string(), unquote(v))
+    } else {
+        AttributePredicate::Exists(value.trim().to_string())
+    };
+    let key = match &predicate {
+        AttributePredicate::Equal(key, _)
+        | AttributePredicate::NotEqual(key, _)
+        | AttributePredicate::Exists(key) => key,
+    };
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        bail!(
+            "Invalid attribute predicate: {} (expected e.g. gene_biotype==protein_coding)",
+            value
+        );
+    }
+    Ok(predicate)
+}
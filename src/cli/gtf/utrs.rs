@@ -0,0 +1,41 @@
+use super::TranscriptModelOptions;
+use crate::cli::{Output, SingleInputGtf};
+use clap::{Parser, ValueEnum};
+
+#[derive(Parser, Debug, Clone)]
+/// Write the 5' or 3' UTRs of the coding transcripts of a GTF file
+///
+/// UTRs are the parts of the exons outside of the coding span (the first to the
+/// last base of the CDS features) and are oriented by the transcript strand.
+/// Non-coding transcripts have no UTRs.
+pub struct GtfUtrsArgs {
+    #[clap(flatten)]
+    pub input: SingleInputGtf,
+
+    #[clap(flatten)]
+    pub model: TranscriptModelOptions,
+
+    #[clap(flatten)]
+    pub params: GtfUtrsParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Parameters")]
+pub struct GtfUtrsParams {
+    /// UTR to report
+    #[clap(short, long)]
+    pub end: UtrEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum UtrEnd {
+    /// 5' UTR (upstream of the CDS)
+    #[value(alias = "5")]
+    Five,
+    /// 3' UTR (downstream of the CDS)
+    #[value(alias = "3")]
+    Three,
+}
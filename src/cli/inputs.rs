@@ -1,5 +1,8 @@
 use crate::{
-    io::{match_bam_input, match_bcf_input, BedReader},
+    io::{
+        match_bam_input, match_bam_region_input, match_bcf_input, match_bcf_region_input,
+        BedReader, RegionBamReader, RegionBcfReader,
+    },
     types::{FieldFormat, InputFormat},
 };
 use anyhow::{bail, Result};
@@ -33,6 +36,21 @@ pub struct SingleInputBam {
     /// Input BAM file to process (default=stdin)
     #[clap(short, long)]
     pub input: Option<String>,
+
+    /// Reference FASTA used to decode CRAM input (must be indexed with `samtools faidx`)
+    #[clap(short = 'R', long)]
+    pub reference: Option<String>,
+}
+impl SingleInputBam {
+    pub fn get_reader(&self) -> Result<BamReader> {
+        match_bam_input(self.input.clone(), self.reference.clone())
+    }
+
+    /// Opens an indexed reader if the BAM file has an index so that
+    /// only records within a set of regions are fetched
+    pub fn get_region_reader(&self) -> Result<RegionBamReader> {
+        match_bam_region_input(self.input.clone(), self.reference.clone())
+    }
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -44,6 +62,10 @@ pub struct MixedInputBam {
     /// Input BED file to process
     #[clap(short = 'b', long)]
     pub bed: String,
+
+    /// Reference FASTA used to decode CRAM input (must be indexed with `samtools faidx`)
+    #[clap(short = 'R', long)]
+    pub reference: Option<String>,
 }
 impl MixedInputBam {
     pub fn get_reader_bed(&self) -> Result<BedReader> {
@@ -51,8 +73,70 @@ impl MixedInputBam {
         BedReader::from_path(Some(self.bed.clone()), None, Some(FieldFormat::StringBased))
     }
 
+    /// Opens an indexed reader if the BAM file has a `.bai` or `.csi` index
+    /// so that only records within the BED regions are fetched
+    pub fn get_reader_bam(&self) -> Result<RegionBamReader> {
+        match_bam_region_input(self.bam.clone(), self.reference.clone())
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Mixed BAM/GTF Dual Input")]
+pub struct MixedInputGtf {
+    /// Input BAM file to process (default=stdin)
+    #[clap(short = 'a', long)]
+    pub bam: Option<String>,
+    /// Input GTF file to process
+    #[clap(short = 'g', long)]
+    pub gtf: String,
+
+    /// Reference FASTA used to decode CRAM input (must be indexed with `samtools faidx`)
+    #[clap(short = 'R', long)]
+    pub reference: Option<String>,
+}
+impl MixedInputGtf {
+    pub fn get_reader_gtf(&self) -> Result<BedReader> {
+        // The GTF must always be read as string-based to recover the attributes
+        BedReader::from_path(
+            Some(self.gtf.clone()),
+            Some(InputFormat::Gtf),
+            Some(FieldFormat::StringBased),
+        )
+    }
+
     pub fn get_reader_bam(&self) -> Result<BamReader> {
-        match_bam_input(self.bam.clone())
+        match_bam_input(self.bam.clone(), self.reference.clone())
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Single GTF Input Options")]
+pub struct SingleInputGtf {
+    /// Input GTF file to process (default=stdin)
+    #[clap(short, long)]
+    pub input: Option<String>,
+}
+impl SingleInputGtf {
+    pub fn get_reader(&self) -> Result<BedReader> {
+        // The GTF must always be read as string-based to recover the attributes
+        BedReader::from_path(
+            self.input.clone(),
+            Some(InputFormat::Gtf),
+            Some(FieldFormat::StringBased),
+        )
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Single BCF Input Options")]
+pub struct SingleInputVcf {
+    /// Input BCF/VCF file to process (default=stdin)
+    #[clap(short, long)]
+    pub input: Option<String>,
+}
+impl SingleInputVcf {
+    pub fn get_reader(&self) -> Result<BcfReader> {
+        match_bcf_input(self.input.clone())
     }
 }
 
@@ -75,6 +159,33 @@ impl MixedInputVcf {
     pub fn get_reader_bcf(&self) -> Result<BcfReader> {
         match_bcf_input(self.bcf.clone())
     }
+
+    /// Opens an indexed reader if the BCF/VCF file has a `.csi` or `.tbi` index
+    /// so that only records within the BED regions are fetched
+    pub fn get_region_reader_bcf(&self) -> Result<RegionBcfReader> {
+        match_bcf_region_input(self.bcf.clone())
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+#[clap(next_help_heading = "Dual BCF Input Options")]
+pub struct DualInputVcf {
+    /// Primary BCF/VCF file to use (default=stdin)
+    #[clap(short, long)]
+    pub a: Option<String>,
+
+    /// Secondary BCF/VCF file to use
+    #[clap(short, long)]
+    pub b: String,
+}
+impl DualInputVcf {
+    pub fn get_reader_a(&self) -> Result<BcfReader> {
+        match_bcf_input(self.a.clone())
+    }
+
+    pub fn get_reader_b(&self) -> Result<BcfReader> {
+        match_bcf_input(Some(self.b.clone()))
+    }
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -119,23 +230,27 @@ impl DualInput {
     pub fn reader_from_b(&self) -> Result<BedReader> {
         BedReader::from_path(Some(self.b[0].clone()), None, None)
     }
+    /// Get readers for both input files, reading both as named if either is named
     pub fn get_readers(self) -> Result<(BedReader, BedReader)> {
         let bed_a = self.reader_from_a()?;
         let bed_b = self.reader_from_b()?;
         if bed_a.is_named() != bed_b.is_named() {
-            bail!("Input files must both be either named or unnamed");
+            return Ok((bed_a.into_named(), bed_b.into_named()));
         }
-        Ok((self.reader_from_a()?, self.reader_from_b()?))
+        Ok((bed_a, bed_b))
     }
+    /// Get readers for all input files, reading all as named if any is named
     pub fn get_multi_readers(self) -> Result<(BedReader, Vec<BedReader>)> {
         let bed_a = self.reader_from_a()?;
         let bed_b = self.readers_from_b()?;
-        for reader in bed_b.iter() {
-            if bed_a.is_named() != reader.is_named() {
-                bail!("Input files must all be either named or unnamed");
-            }
+        if bed_b
+            .iter()
+            .any(|reader| reader.is_named() != bed_a.is_named())
+        {
+            let bed_b = bed_b.into_iter().map(BedReader::into_named).collect();
+            return Ok((bed_a.into_named(), bed_b));
         }
-        Ok((self.reader_from_a()?, self.readers_from_b()?))
+        Ok((bed_a, bed_b))
     }
     pub fn is_multi(&self) -> bool {
         self.b.len() > 1
@@ -150,20 +265,14 @@ pub struct MultiInput {
     pub inputs: Vec<String>,
 }
 impl MultiInput {
-    /// Get readers for all input files and ensure they are all named or unnamed
+    /// Get readers for all input files, reading all as named if any is named
     pub fn get_readers(self) -> Result<Vec<BedReader>> {
         let mut readers = vec![];
-        let mut named = None;
         for input in self.inputs {
-            let reader = BedReader::from_path(Some(input), None, None)?;
-            if let Some(named) = named {
-                if named != reader.is_named() {
-                    bail!("Input files must all be either named or unnamed");
-                }
-            } else {
-                named = Some(reader.is_named());
-            }
-            readers.push(reader);
+            readers.push(BedReader::from_path(Some(input), None, None)?);
+        }
+        if readers.iter().any(|reader| reader.is_named()) {
+            readers = readers.into_iter().map(BedReader::into_named).collect();
         }
         Ok(readers)
     }
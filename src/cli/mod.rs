@@ -8,13 +8,16 @@ mod coverage;
 mod extend;
 mod flank;
 mod get_fasta;
+pub mod gff;
 mod growth;
+pub mod gtf;
 mod inputs;
 mod intersect;
 mod join;
 mod merge;
 mod outputs;
 mod overlap_predicates;
+pub mod peak;
 mod random;
 mod sample;
 mod segment;
@@ -35,7 +38,8 @@ pub use flank::FlankArgs;
 pub use get_fasta::{GetFastaArgs, GetFastaParams};
 pub use growth::Growth;
 pub use inputs::{
-    DualInput, MixedInputBam, MixedInputVcf, MultiInput, SingleInput, SingleInputBam,
+    DualInput, DualInputVcf, MixedInputBam, MixedInputGtf, MixedInputVcf, MultiInput, SingleInput,
+    SingleInputBam, SingleInputGtf, SingleInputVcf,
 };
 pub use intersect::{IntersectArgs, IntersectParams, OutputMethod};
 pub use join::{JoinArgs, JoinMethod, JoinParams};
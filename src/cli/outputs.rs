@@ -3,7 +3,10 @@ use anyhow::Result;
 use clap::{Parser, ValueEnum};
 use rust_htslib::{
     bam::{Format as SamFormat, HeaderView as BamHeaderView, Writer as BamWriter},
-    bcf::{header::HeaderView as VcfHeaderView, Format as VcfFormat, Writer as VcfWriter},
+    bcf::{
+        header::HeaderView as VcfHeaderView, Format as VcfFormat, Header as VcfHeader,
+        Writer as VcfWriter,
+    },
 };
 use std::io::Write;
 
@@ -49,6 +52,10 @@ pub struct BamOutput {
     /// Threads to use when writing BAM files
     #[clap(short = 't', long, default_value = "1")]
     pub threads: usize,
+
+    /// Reference FASTA used to encode CRAM output (defaults to the input reference)
+    #[clap(long)]
+    pub output_reference: Option<String>,
 }
 impl BamOutput {
     pub fn get_writer(&self, header: &BamHeaderView) -> Result<BamWriter> {
@@ -57,8 +64,27 @@ impl BamOutput {
             header,
             self.format.into(),
             self.threads,
+            self.output_reference.clone(),
+        )
+    }
+
+    /// Opens an additional writer at the path with the same format, threads, and reference
+    pub fn get_writer_to(&self, path: String, header: &BamHeaderView) -> Result<BamWriter> {
+        match_bam_output(
+            Some(path),
+            header,
+            self.format.into(),
+            self.threads,
+            self.output_reference.clone(),
         )
     }
+
+    /// Falls back to the input reference if no output reference was provided
+    pub fn inherit_reference(&mut self, reference: Option<&String>) {
+        if self.output_reference.is_none() {
+            self.output_reference = reference.cloned();
+        }
+    }
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -82,6 +108,11 @@ pub struct VcfOutput {
 }
 impl VcfOutput {
     pub fn get_writer(&self, header: &VcfHeaderView) -> Result<VcfWriter> {
+        self.get_writer_with_header(&VcfHeader::from_template(header))
+    }
+
+    /// Opens the writer with a header that differs from the input (e.g. with added INFO lines)
+    pub fn get_writer_with_header(&self, header: &VcfHeader) -> Result<VcfWriter> {
         match_bcf_output(
             self.output.clone(),
             header,
@@ -98,6 +129,15 @@ pub enum WrapSamFormat {
     Sam,
     Cram,
 }
+impl WrapSamFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            WrapSamFormat::Bam => "bam",
+            WrapSamFormat::Sam => "sam",
+            WrapSamFormat::Cram => "cram",
+        }
+    }
+}
 impl From<WrapSamFormat> for SamFormat {
     fn from(format: WrapSamFormat) -> Self {
         match format {
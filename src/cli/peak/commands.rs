@@ -0,0 +1,14 @@
+use super::{PeakMergeArgs, PeakSortArgs, SummitArgs};
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+pub enum PeakCommand {
+    /// Merge overlapping peaks and keep the strongest summit
+    Merge(PeakMergeArgs),
+
+    /// Sort peaks by their significance
+    Sort(PeakSortArgs),
+
+    /// Re-centre peaks on their summits
+    Summit(SummitArgs),
+}
@@ -0,0 +1,28 @@
+use super::PeakStat;
+use crate::cli::{Output, SingleInput};
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+/// Merge overlapping or bookended narrowPeak/broadPeak records
+///
+/// Each merged peak spans all of its members and keeps the name, score, strand,
+/// statistics, and summit of its strongest member. The output is sorted by
+/// chromosome name and start.
+pub struct PeakMergeArgs {
+    #[clap(flatten)]
+    pub input: SingleInput,
+
+    #[clap(flatten)]
+    pub params: PeakMergeParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+#[derive(Parser, Debug)]
+#[clap(next_help_heading = "Parameters")]
+pub struct PeakMergeParams {
+    /// Statistic used to pick the strongest peak of each merged group
+    #[clap(short, long, default_value = "signal")]
+    pub by: PeakStat,
+}
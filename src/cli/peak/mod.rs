@@ -0,0 +1,11 @@
+mod commands;
+mod merge;
+mod sort;
+mod stat;
+mod summit;
+
+pub use commands::PeakCommand;
+pub use merge::PeakMergeArgs;
+pub use sort::PeakSortArgs;
+pub use stat::PeakStat;
+pub use summit::SummitArgs;
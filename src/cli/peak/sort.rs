@@ -0,0 +1,26 @@
+use super::PeakStat;
+use crate::cli::{Output, SingleInput};
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+/// Sort narrowPeak/broadPeak records from most to least significant
+///
+/// Peaks with the same value keep their input order.
+pub struct PeakSortArgs {
+    #[clap(flatten)]
+    pub input: SingleInput,
+
+    #[clap(flatten)]
+    pub params: PeakSortParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+#[derive(Parser, Debug)]
+#[clap(next_help_heading = "Parameters")]
+pub struct PeakSortParams {
+    /// Statistic to sort by
+    #[clap(short, long, default_value = "q-value")]
+    pub by: PeakStat,
+}
@@ -0,0 +1,22 @@
+use crate::types::Peak;
+use clap::ValueEnum;
+
+/// Peak statistic used to rank peaks
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PeakStat {
+    /// signalValue (column 7)
+    Signal,
+    /// pValue as -log10 (column 8)
+    PValue,
+    /// qValue as -log10 (column 9)
+    QValue,
+}
+impl PeakStat {
+    pub fn value(&self, peak: &Peak) -> f64 {
+        match self {
+            PeakStat::Signal => peak.signal_value,
+            PeakStat::PValue => peak.p_value,
+            PeakStat::QValue => peak.q_value,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::cli::{Output, SingleInput};
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+/// Re-centre narrowPeak records on their summits
+///
+/// Each peak is replaced by its single base summit extended on both sides, and the
+/// peak offset is updated to point at the same summit. Peaks without a called
+/// summit (a peak offset of -1) are centred on their midpoint.
+pub struct SummitArgs {
+    #[clap(flatten)]
+    pub input: SingleInput,
+
+    #[clap(flatten)]
+    pub params: SummitParams,
+
+    #[clap(flatten)]
+    pub output: Output,
+}
+
+#[derive(Parser, Debug)]
+#[clap(next_help_heading = "Parameters")]
+pub struct SummitParams {
+    /// Bases to extend on each side of the summit
+    ///
+    /// The re-centred peaks span `2 * N + 1` bases (less at the chromosome ends).
+    #[clap(short, long, default_value = "0")]
+    pub extend: usize,
+
+    /// Genome file to clamp the re-centred peaks to the chromosome ends
+    ///
+    /// Without a genome file the peaks can run past the chromosome ends.
+    #[clap(short, long)]
+    pub genome: Option<String>,
+}
@@ -0,0 +1,81 @@
+use super::utils::{get_stranded_bed3, passes_record_predicates, read_named_set};
+use crate::{
+    cli::bam::{AnnotateArgs, AnnotateParams},
+    io::RegionBamReader,
+    types::{Bed6Set, SplitTranslater, Translate},
+};
+use anyhow::Result;
+use rust_htslib::bam::{record::Aux, Record, Writer as BamWriter};
+
+/// Replaces the value of the tag with the names joined by the delimiter
+fn tag_record(record: &Record, names: &[&str], params: &AnnotateParams) -> Result<Record> {
+    let tag = params.tag.as_bytes();
+    let value = names.join(&params.delimiter);
+    let mut record = record.clone();
+    if record.aux(tag).is_ok() {
+        record.remove_aux(tag)?;
+    }
+    record.push_aux(tag, Aux::String(&value))?;
+    Ok(record)
+}
+
+fn run_annotate(
+    bam: &mut RegionBamReader,
+    mut set: Bed6Set,
+    translater: &SplitTranslater,
+    params: AnnotateParams,
+    writer: &mut BamWriter,
+) -> Result<()> {
+    // Get the header
+    let header = bam.header().clone();
+
+    // Sort the BED Set
+    set.sort();
+
+    // Initialize the overlap query method
+    let query_method = params.overlap_predicates.into();
+
+    // Reusable buffer of the overlapping interval names
+    let mut names: Vec<&str> = Vec::new();
+
+    // Every record is written back out so the full file is visited
+    bam.for_each_record(None, |record| {
+        if record.is_unmapped()
+            || record.tid() < 0
+            || !passes_record_predicates(record, &params.record_predicates)
+        {
+            writer.write(record)?;
+            return Ok(());
+        }
+        names.clear();
+        if let Some(bed) = get_stranded_bed3(record, &header, translater)? {
+            for iv in set.query_iter(&bed, query_method)? {
+                let name = translater.get_meta_name(*iv.name()).unwrap_or_default();
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        if names.is_empty() {
+            writer.write(record)?;
+        } else {
+            writer.write(&tag_record(record, &names, &params)?)?;
+        }
+        Ok(())
+    })
+}
+
+/// Writes every BAM record back out and stores the names of the
+/// overlapping BED intervals in an aux tag.
+///
+/// Records overlapping multiple intervals with the same name only
+/// report that name once.
+pub fn annotate(mut args: AnnotateArgs) -> Result<()> {
+    args.output
+        .inherit_reference(args.inputs.reference.as_ref());
+    let bed_reader = args.inputs.get_reader_bed()?;
+    let (set, translater) = read_named_set(bed_reader)?;
+    let mut bam_reader = args.inputs.get_reader_bam()?;
+    let mut writer = args.output.get_writer(bam_reader.header())?;
+    run_annotate(&mut bam_reader, set, &translater, args.params, &mut writer)
+}
@@ -1,6 +1,7 @@
 use crate::cli::bam::ConvertParams;
 use crate::commands::bam::utils::{
-    get_strand, parse_chr_name, parse_endpoints, parse_mapping_quality, parse_query_name,
+    get_strand, parse_blocks, parse_chr_name, parse_endpoints, parse_extra_columns,
+    parse_mapping_quality, parse_query_name, passes_record_predicates,
 };
 use crate::io::build_writer;
 
@@ -13,6 +14,7 @@ fn format_print_record<W: Write>(
     record: &Record,
     header: &HeaderView,
     params: &ConvertParams,
+    extra_columns: &[String],
     wtr: &mut csv::Writer<W>,
 ) -> Result<()> {
     let chr_name = parse_chr_name(record, header)?;
@@ -21,32 +23,72 @@ fn format_print_record<W: Write>(
     let mapq = parse_mapping_quality(record);
     let strand = get_strand(record);
 
+    let tuple = (
+        from_utf8(chr_name)?,
+        start,
+        end,
+        from_utf8(&qname)?,
+        mapq,
+        strand,
+    );
     if params.bed.cigar {
         let cigar = record.cigar();
-        let tuple = (
-            from_utf8(chr_name)?,
-            start,
-            end,
-            from_utf8(&qname)?,
-            mapq,
-            strand,
-            format!("{}", cigar),
-        );
-        wtr.serialize(tuple)?;
+        wtr.serialize((tuple, format!("{}", cigar), extra_columns))?;
     } else {
-        let tuple = (
-            from_utf8(chr_name)?,
-            start,
-            end,
-            from_utf8(&qname)?,
-            mapq,
-            strand,
-        );
-        wtr.serialize(tuple)?;
+        wtr.serialize((tuple, extra_columns))?;
     }
     Ok(())
 }
 
+fn format_print_record_bed12<W: Write>(
+    record: &Record,
+    header: &HeaderView,
+    params: &ConvertParams,
+    extra_columns: &[String],
+    wtr: &mut csv::Writer<W>,
+) -> Result<()> {
+    let chr_name = parse_chr_name(record, header)?;
+    let qname = parse_query_name(record)?;
+    let mapq = parse_mapping_quality(record);
+    let strand = get_strand(record);
+    let blocks = parse_blocks(record, params.bed.split_deletions);
+
+    // The interval spans the blocks so a splitting deletion at either end of the
+    // alignment is not part of it
+    let (start, end) = match (blocks.first(), blocks.last()) {
+        (Some((start, _)), Some((_, end))) => (*start, *end),
+        _ => parse_endpoints(record)?,
+    };
+
+    let block_sizes = blocks
+        .iter()
+        .map(|(b_start, b_end)| (b_end - b_start).to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    let block_starts = blocks
+        .iter()
+        .map(|(b_start, _)| (b_start - start).to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let tuple = (
+        from_utf8(chr_name)?,
+        start,
+        end,
+        from_utf8(&qname)?,
+        mapq,
+        strand,
+        start,
+        end,
+        0,
+        blocks.len(),
+        block_sizes,
+        block_starts,
+    );
+    wtr.serialize((tuple, extra_columns))?;
+    Ok(())
+}
+
 pub fn convert_bed(mut bam: BamReader, params: ConvertParams) -> Result<()> {
     if params.threads > 1 {
         bam.set_threads(params.threads)?;
@@ -54,9 +96,46 @@ pub fn convert_bed(mut bam: BamReader, params: ConvertParams) -> Result<()> {
     let header = bam.header().clone();
     let mut wtr = build_writer(stdout());
     let mut record = Record::new();
+    let mut extra_columns = Vec::new();
+    while let Some(result) = bam.read(&mut record) {
+        result?;
+        if !passes_record_predicates(&record, &params.record_predicates) {
+            continue;
+        }
+        parse_extra_columns(
+            &record,
+            &header,
+            &params.bed.columns,
+            &params.bed.missing,
+            &mut extra_columns,
+        )?;
+        format_print_record(&record, &header, &params, &extra_columns, &mut wtr)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn convert_bed12(mut bam: BamReader, params: ConvertParams) -> Result<()> {
+    if params.threads > 1 {
+        bam.set_threads(params.threads)?;
+    }
+    let header = bam.header().clone();
+    let mut wtr = build_writer(stdout());
+    let mut record = Record::new();
+    let mut extra_columns = Vec::new();
     while let Some(result) = bam.read(&mut record) {
         result?;
-        format_print_record(&record, &header, &params, &mut wtr)?;
+        if !passes_record_predicates(&record, &params.record_predicates) {
+            continue;
+        }
+        parse_extra_columns(
+            &record,
+            &header,
+            &params.bed.columns,
+            &params.bed.missing,
+            &mut extra_columns,
+        )?;
+        format_print_record_bed12(&record, &header, &params, &extra_columns, &mut wtr)?;
     }
     wtr.flush()?;
     Ok(())
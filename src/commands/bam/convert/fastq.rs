@@ -0,0 +1,254 @@
+use crate::cli::bam::{ConvertParams, FastqConversionParams};
+use crate::commands::bam::utils::{parse_query_name, passes_record_predicates};
+use crate::io::match_output;
+
+use anyhow::Result;
+use hashbrown::HashMap;
+use rust_htslib::bam::{Read, Reader as BamReader, Record};
+use std::io::Write;
+
+/// Phred offset used when encoding quality scores in FASTQ
+const PHRED_OFFSET: u8 = 33;
+
+/// Highest quality score that can be encoded as a printable FASTQ character (`~`)
+const MAX_PHRED: u8 = 126 - PHRED_OFFSET;
+
+/// Value used by the BAM specification to denote a missing quality string
+const MISSING_QUALITY: u8 = 0xff;
+
+fn complement(base: u8) -> u8 {
+    match base {
+        b'A' => b'T',
+        b'C' => b'G',
+        b'G' => b'C',
+        b'T' => b'A',
+        b'a' => b't',
+        b'c' => b'g',
+        b'g' => b'c',
+        b't' => b'a',
+        _ => base,
+    }
+}
+
+/// Fills the sequence and quality buffers with the original read
+///
+/// Reverse strand alignments are stored reverse complemented in the BAM
+/// so they are flipped back to the orientation they were sequenced in.
+fn fill_buffers(record: &Record, seq_buffer: &mut Vec<u8>, qual_buffer: &mut Vec<u8>) {
+    seq_buffer.clear();
+    qual_buffer.clear();
+
+    seq_buffer.extend(record.seq().as_bytes());
+    let qual = record.qual();
+    if qual.first() == Some(&MISSING_QUALITY) {
+        qual_buffer.resize(seq_buffer.len(), PHRED_OFFSET);
+    } else {
+        qual_buffer.extend(qual.iter().map(|q| q.min(&MAX_PHRED) + PHRED_OFFSET));
+    }
+
+    if record.is_reverse() {
+        seq_buffer.reverse();
+        seq_buffer.iter_mut().for_each(|b| *b = complement(*b));
+        qual_buffer.reverse();
+    }
+}
+
+fn skip_record(record: &Record, params: &FastqConversionParams) -> bool {
+    // Records without a stored sequence cannot be represented in FASTQ
+    record.seq_len() == 0
+        || (!params.include_secondary && record.is_secondary())
+        || (!params.include_supplementary && record.is_supplementary())
+}
+
+/// Only primary alignments of paired reads are matched with their mate
+fn is_mate(record: &Record) -> bool {
+    record.is_paired() && !record.is_secondary() && !record.is_supplementary()
+}
+
+/// A read held until its mate is found
+struct BufferedMate {
+    name: Vec<u8>,
+    seq: Vec<u8>,
+    qual: Vec<u8>,
+    is_first: bool,
+}
+
+fn write_fastq_record<W: Write>(name: &[u8], seq: &[u8], qual: &[u8], wtr: &mut W) -> Result<()> {
+    wtr.write_all(b"@")?;
+    wtr.write_all(name)?;
+    wtr.write_all(b"\n")?;
+    wtr.write_all(seq)?;
+    wtr.write_all(b"\n+\n")?;
+    wtr.write_all(qual)?;
+    wtr.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Returns the writer for reads without a mate in the output
+///
+/// These are dropped if mates are split over R1 and R2 and no singletons
+/// output was provided, as writing them to R1 would desync the pairs.
+fn singleton_writer<'a>(
+    r1: &'a mut Box<dyn Write>,
+    r2: &Option<Box<dyn Write>>,
+    singletons: &'a mut Option<Box<dyn Write>>,
+) -> Option<&'a mut Box<dyn Write>> {
+    match singletons {
+        Some(singletons) => Some(singletons),
+        None if r2.is_none() => Some(r1),
+        None => None,
+    }
+}
+
+pub fn convert_fastq(mut bam: BamReader, params: ConvertParams) -> Result<()> {
+    if params.threads > 1 {
+        bam.set_threads(params.threads)?;
+    }
+    let fastq = params.fastq;
+
+    // Build the R1 writer (stdout by default) and the optional R2 and singleton writers
+    let build_optional_writer = |path: Option<String>| -> Result<Option<Box<dyn Write>>> {
+        path.map(|path| {
+            match_output(
+                Some(path),
+                fastq.compression_threads,
+                fastq.compression_level,
+            )
+        })
+        .transpose()
+    };
+    let mut r1 = match_output(
+        fastq.r1.clone(),
+        fastq.compression_threads,
+        fastq.compression_level,
+    )?;
+    let mut r2 = build_optional_writer(fastq.r2.clone())?;
+    let mut singletons = build_optional_writer(fastq.singletons.clone())?;
+
+    // Initialize the reusable buffers to avoid repeated allocations
+    let mut record = Record::new();
+    let mut seq_buffer = Vec::new();
+    let mut qual_buffer = Vec::new();
+
+    // Mates are matched by name as they are not adjacent in coordinate sorted files
+    let mut mates: HashMap<Vec<u8>, BufferedMate> = HashMap::new();
+
+    while let Some(result) = bam.read(&mut record) {
+        result?;
+        if skip_record(&record, &fastq)
+            || !passes_record_predicates(&record, &params.record_predicates)
+        {
+            continue;
+        }
+        let name = parse_query_name(&record)?;
+        fill_buffers(&record, &mut seq_buffer, &mut qual_buffer);
+
+        if !is_mate(&record) {
+            if let Some(wtr) = singleton_writer(&mut r1, &r2, &mut singletons) {
+                write_fastq_record(&name, &seq_buffer, &qual_buffer, wtr)?;
+            }
+            continue;
+        }
+
+        let mate = match mates.remove(record.qname()) {
+            Some(mate) => mate,
+            None => {
+                let mate = BufferedMate {
+                    name,
+                    seq: seq_buffer.clone(),
+                    qual: qual_buffer.clone(),
+                    is_first: record.is_first_in_template(),
+                };
+                mates.insert(record.qname().to_vec(), mate);
+                continue;
+            }
+        };
+        let current = BufferedMate {
+            name,
+            seq: std::mem::take(&mut seq_buffer),
+            qual: std::mem::take(&mut qual_buffer),
+            is_first: record.is_first_in_template(),
+        };
+        let (first, second) = if mate.is_first {
+            (mate, current)
+        } else {
+            (current, mate)
+        };
+        write_fastq_record(&first.name, &first.seq, &first.qual, &mut r1)?;
+        let wtr = r2.as_mut().unwrap_or(&mut r1);
+        write_fastq_record(&second.name, &second.seq, &second.qual, wtr)?;
+    }
+
+    // Mates whose pair was filtered or missing are written in name order
+    let mut orphans = mates.into_values().collect::<Vec<_>>();
+    orphans.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    if let Some(wtr) = singleton_writer(&mut r1, &r2, &mut singletons) {
+        for orphan in orphans {
+            write_fastq_record(&orphan.name, &orphan.seq, &orphan.qual, wtr)?;
+        }
+    }
+
+    r1.flush()?;
+    if let Some(mut r2) = r2 {
+        r2.flush()?;
+    }
+    if let Some(mut singletons) = singletons {
+        singletons.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod testing {
+
+    use super::*;
+    use rust_htslib::bam::record::{Cigar, CigarString};
+
+    fn build_record(reverse: bool) -> Record {
+        let mut record = Record::new();
+        let cigar = CigarString(vec![Cigar::Match(4)]);
+        record.set(b"read", Some(&cigar), b"AACG", &[10, 20, 30, 40]);
+        if reverse {
+            record.set_reverse();
+        }
+        record
+    }
+
+    #[test]
+    fn test_fill_buffers_forward() {
+        let record = build_record(false);
+        let mut seq = Vec::new();
+        let mut qual = Vec::new();
+        fill_buffers(&record, &mut seq, &mut qual);
+        assert_eq!(seq, b"AACG");
+        assert_eq!(qual, b"+5?I");
+    }
+
+    #[test]
+    fn test_fill_buffers_reverse() {
+        let record = build_record(true);
+        let mut seq = Vec::new();
+        let mut qual = Vec::new();
+        fill_buffers(&record, &mut seq, &mut qual);
+        assert_eq!(seq, b"CGTT");
+        assert_eq!(qual, b"I?5+");
+    }
+
+    #[test]
+    fn test_fill_buffers_high_quality() {
+        let mut record = Record::new();
+        let cigar = CigarString(vec![Cigar::Match(3)]);
+        record.set(b"read", Some(&cigar), b"ACG", &[93, 94, 250]);
+        let mut seq = Vec::new();
+        let mut qual = Vec::new();
+        fill_buffers(&record, &mut seq, &mut qual);
+        assert_eq!(qual, b"~~~");
+    }
+
+    #[test]
+    fn test_write_fastq_record() {
+        let mut output = Vec::new();
+        write_fastq_record(b"read", b"ACGT", b"IIII", &mut output).unwrap();
+        assert_eq!(output, b"@read\nACGT\n+\nIIII\n");
+    }
+}
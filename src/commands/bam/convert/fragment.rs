@@ -0,0 +1,140 @@
+use crate::cli::bam::ConvertParams;
+use crate::commands::bam::utils::{
+    get_strand, parse_chr_name, parse_endpoints, parse_extra_columns, parse_mapping_quality,
+    passes_record_predicates,
+};
+use crate::io::build_writer;
+
+use anyhow::Result;
+use hashbrown::HashMap;
+use rust_htslib::bam::{HeaderView, Read, Reader as BamReader, Record};
+use std::io::{stdout, Write};
+use std::str::from_utf8;
+
+/// Only primary alignments of properly paired mates are joined into fragments
+fn is_fragment_mate(record: &Record) -> bool {
+    record.is_paired()
+        && record.is_proper_pair()
+        && !record.is_unmapped()
+        && !record.is_mate_unmapped()
+        && !record.is_secondary()
+        && !record.is_supplementary()
+}
+
+/// Writes the insert span covered by both mates as a BED3 record
+fn format_print_fragment<W: Write>(
+    first: &Record,
+    second: &Record,
+    header: &HeaderView,
+    extra_columns: &[String],
+    wtr: &mut csv::Writer<W>,
+) -> Result<()> {
+    // Mates on different chromosomes do not define a contiguous fragment
+    if first.tid() != second.tid() {
+        return Ok(());
+    }
+    let chr_name = parse_chr_name(first, header)?;
+    let (first_start, first_end) = parse_endpoints(first)?;
+    let (second_start, second_end) = parse_endpoints(second)?;
+    let tuple = (
+        from_utf8(chr_name)?,
+        first_start.min(second_start),
+        first_end.max(second_end),
+    );
+    wtr.serialize((tuple, extra_columns))?;
+    Ok(())
+}
+
+/// Writes both mates as a BEDPE record using the lowest mapping quality as the score
+fn format_print_bedpe<W: Write>(
+    first: &Record,
+    second: &Record,
+    header: &HeaderView,
+    extra_columns: &[String],
+    wtr: &mut csv::Writer<W>,
+) -> Result<()> {
+    let first_chr = parse_chr_name(first, header)?;
+    let second_chr = parse_chr_name(second, header)?;
+    let (first_start, first_end) = parse_endpoints(first)?;
+    let (second_start, second_end) = parse_endpoints(second)?;
+    let mapq = parse_mapping_quality(first).min(parse_mapping_quality(second));
+    let tuple = (
+        from_utf8(first_chr)?,
+        first_start,
+        first_end,
+        from_utf8(second_chr)?,
+        second_start,
+        second_end,
+        from_utf8(first.qname())?,
+        mapq,
+        get_strand(first),
+        get_strand(second),
+    );
+    wtr.serialize((tuple, extra_columns))?;
+    Ok(())
+}
+
+/// Joins mates by their query name and formats each completed pair
+///
+/// Mates are buffered until their partner is seen so this works for both
+/// name-sorted and coordinate-sorted input. Coordinate-sorted input will
+/// require more memory as mates may be far apart in the file.
+///
+/// Pairs are written as soon as their second mate is read, so the output is
+/// not sorted by fragment start even for coordinate-sorted input.
+///
+/// Extra columns are taken from the first mate of each pair.
+fn convert_pairs<F>(mut bam: BamReader, params: ConvertParams, format_pair: F) -> Result<()>
+where
+    F: Fn(
+        &Record,
+        &Record,
+        &HeaderView,
+        &[String],
+        &mut csv::Writer<std::io::Stdout>,
+    ) -> Result<()>,
+{
+    if params.threads > 1 {
+        bam.set_threads(params.threads)?;
+    }
+    let header = bam.header().clone();
+    let mut wtr = build_writer(stdout());
+    let mut mates: HashMap<Vec<u8>, Record> = HashMap::new();
+    let mut record = Record::new();
+    let mut extra_columns = Vec::new();
+    while let Some(result) = bam.read(&mut record) {
+        result?;
+        if !is_fragment_mate(&record)
+            || !passes_record_predicates(&record, &params.record_predicates)
+        {
+            continue;
+        }
+        if let Some(mate) = mates.remove(record.qname()) {
+            let (first, second) = if record.is_first_in_template() {
+                (&record, &mate)
+            } else {
+                (&mate, &record)
+            };
+            parse_extra_columns(
+                first,
+                &header,
+                &params.bed.columns,
+                &params.bed.missing,
+                &mut extra_columns,
+            )?;
+            format_pair(first, second, &header, &extra_columns, &mut wtr)?;
+        } else {
+            mates.insert(record.qname().to_vec(), record.clone());
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn convert_fragment(bam: BamReader, params: ConvertParams) -> Result<()> {
+    convert_pairs(bam, params, format_print_fragment)
+}
+
+pub fn convert_bedpe(bam: BamReader, params: ConvertParams) -> Result<()> {
+    convert_pairs(bam, params, format_print_bedpe)
+}
@@ -1,22 +1,26 @@
 mod bed;
-pub use bed::convert_bed;
+mod fastq;
+mod fragment;
+pub use bed::{convert_bed, convert_bed12};
+pub use fastq::convert_fastq;
+pub use fragment::{convert_bedpe, convert_fragment};
 
 use crate::cli::bam::{BamConversionType, ConvertArgs, ConvertParams};
-use crate::io::match_bam_input;
 
-use anyhow::{bail, Result};
+use anyhow::Result;
 use rust_htslib::bam::Reader as BamReader;
 
 fn dispatch_conversion(bam: BamReader, params: ConvertParams) -> Result<()> {
     match params.conv {
         BamConversionType::Bed => convert_bed(bam, params),
-        _ => bail!(
-            "FASTQ conversion is not implemented yet - but checkout samtools fastq for a solution"
-        ),
+        BamConversionType::Bed12 => convert_bed12(bam, params),
+        BamConversionType::Fragment => convert_fragment(bam, params),
+        BamConversionType::Bedpe => convert_bedpe(bam, params),
+        BamConversionType::Fastq => convert_fastq(bam, params),
     }
 }
 
 pub fn convert(args: ConvertArgs) -> Result<()> {
-    let bam = match_bam_input(args.input.input)?;
+    let bam = args.input.get_reader()?;
     dispatch_conversion(bam, args.params)
 }
@@ -0,0 +1,321 @@
+use super::utils::{is_multi_mapping, parse_blocks, parse_chr_name, passes_record_predicates};
+use crate::{
+    cli::bam::{CountArgs, CountMode, CountParams},
+    io::{build_writer, match_output},
+    types::{GtfSet, NumericBed6, SplitTranslater, Translate},
+    utils::parse_gtf_attribute,
+};
+use anyhow::{bail, Result};
+use bedrs::{
+    types::{Query, QueryMethod},
+    Coordinates, IntervalContainer, Score, Strand, StrandedBed3,
+};
+use hashbrown::{HashMap, HashSet};
+use rust_htslib::bam::{HeaderView, Read, Reader as BamReader, Record};
+use std::{io::Write, str::from_utf8};
+
+type Block = StrandedBed3<usize, usize>;
+
+/// The outcome of assigning a read (or read pair) to a gene
+enum Assignment {
+    Assigned(usize),
+    Unmapped,
+    Filtered,
+    MultiMapping,
+    NoFeatures,
+    Ambiguity,
+}
+
+#[derive(Default)]
+struct CountSummary {
+    assigned: usize,
+    unmapped: usize,
+    filtered: usize,
+    multi_mapping: usize,
+    no_features: usize,
+    ambiguity: usize,
+}
+impl CountSummary {
+    fn add(&mut self, assignment: Assignment, counts: &mut [usize]) {
+        match assignment {
+            Assignment::Assigned(idx) => {
+                counts[idx] += 1;
+                self.assigned += 1;
+            }
+            Assignment::Unmapped => self.unmapped += 1,
+            Assignment::Filtered => self.filtered += 1,
+            Assignment::MultiMapping => self.multi_mapping += 1,
+            Assignment::NoFeatures => self.no_features += 1,
+            Assignment::Ambiguity => self.ambiguity += 1,
+        }
+    }
+
+    fn write<W: Write>(&self, writer: W) -> Result<()> {
+        let mut wtr = build_writer(writer);
+        wtr.serialize(("Assigned", self.assigned))?;
+        wtr.serialize(("Unassigned_Unmapped", self.unmapped))?;
+        wtr.serialize(("Unassigned_Filtered", self.filtered))?;
+        wtr.serialize(("Unassigned_MultiMapping", self.multi_mapping))?;
+        wtr.serialize(("Unassigned_NoFeatures", self.no_features))?;
+        wtr.serialize(("Unassigned_Ambiguity", self.ambiguity))?;
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// Features of a GTF grouped into genes
+///
+/// The name of each feature interval is the index of its gene
+struct GeneModel {
+    features: IntervalContainer<NumericBed6, usize, usize>,
+    genes: Vec<String>,
+    translater: SplitTranslater,
+}
+impl GeneModel {
+    fn from_gtf(set: GtfSet, translater: SplitTranslater, params: &CountParams) -> Result<Self> {
+        let mut gene_idx = HashMap::new();
+        let mut genes = Vec::new();
+        let mut features = Vec::new();
+        for iv in set.records() {
+            let feature = translater.get_meta_name(*iv.feature()).unwrap_or_default();
+            let attributes = translater
+                .get_meta_name(*iv.attributes())
+                .unwrap_or_default();
+            if !params.selection.matches(feature, attributes) {
+                continue;
+            }
+            let gene = if let Some(gene) = parse_gtf_attribute(attributes, &params.selection.name) {
+                gene
+            } else {
+                continue;
+            };
+            let idx = *gene_idx.entry(gene.to_string()).or_insert_with(|| {
+                genes.push(gene.to_string());
+                genes.len() - 1
+            });
+            // GTF coordinates are 1-based and closed while BAM records are 0-based and half-open
+            features.push(NumericBed6::new(
+                *iv.chr(),
+                iv.start().saturating_sub(1),
+                iv.end(),
+                idx,
+                Score(None),
+                iv.strand().unwrap_or_default(),
+            ));
+        }
+        if genes.is_empty() {
+            bail!(
+                "No `{}` features with a `{}` attribute were found in the GTF",
+                params.selection.feature.join(","),
+                params.selection.name
+            );
+        }
+        Ok(Self {
+            features: IntervalContainer::from_unsorted(features),
+            genes,
+            translater,
+        })
+    }
+
+    /// Appends the aligned blocks of a record with the strand of its fragment
+    fn push_blocks(
+        &self,
+        record: &Record,
+        header: &HeaderView,
+        blocks: &mut Vec<Block>,
+    ) -> Result<()> {
+        let chr_name = from_utf8(parse_chr_name(record, header)?)?;
+        let chr_idx = if let Some(idx) = self.translater.get_chr_idx(chr_name) {
+            idx
+        } else {
+            return Ok(());
+        };
+        // The second mate is sequenced from the opposite strand of the fragment
+        let reverse = record.is_reverse() != (record.is_paired() && record.is_last_in_template());
+        let strand = if reverse {
+            Strand::Reverse
+        } else {
+            Strand::Forward
+        };
+        for (start, end) in parse_blocks(record, None) {
+            blocks.push(Block::new(chr_idx, start, end, strand));
+        }
+        Ok(())
+    }
+
+    /// Assigns the aligned blocks of a fragment to at most one gene
+    fn assign(&self, blocks: &[Block], params: &CountParams) -> Result<Assignment> {
+        let query = Query::new(QueryMethod::Compare, params.strandedness.into());
+        let mut union = HashSet::new();
+        let mut strict: Option<HashSet<usize>> = None;
+        for block in blocks {
+            let overlaps = self.features.query_iter(block, query)?.collect::<Vec<_>>();
+            union.extend(overlaps.iter().map(|iv| *iv.name()));
+            if let CountMode::IntersectionStrict = params.mode {
+                let covering = covering_genes(block, &overlaps);
+                strict = Some(match strict {
+                    Some(genes) => genes.intersection(&covering).copied().collect(),
+                    None => covering,
+                });
+            }
+        }
+        let candidates = match params.mode {
+            CountMode::Union => union,
+            CountMode::IntersectionStrict => strict.unwrap_or_default(),
+        };
+        let assignment = match candidates.len() {
+            0 => Assignment::NoFeatures,
+            1 => Assignment::Assigned(*candidates.iter().next().unwrap()),
+            _ => Assignment::Ambiguity,
+        };
+        Ok(assignment)
+    }
+}
+
+/// Genes whose features cover every base of the block
+fn covering_genes(block: &Block, overlaps: &[&NumericBed6]) -> HashSet<usize> {
+    let mut spans: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+    for iv in overlaps {
+        spans
+            .entry(*iv.name())
+            .or_default()
+            .push((iv.start(), iv.end()));
+    }
+    spans
+        .into_iter()
+        .filter_map(|(gene, mut gene_spans)| {
+            gene_spans.sort_unstable();
+            let mut covered = block.start();
+            for (start, end) in gene_spans {
+                if start > covered {
+                    break;
+                }
+                covered = covered.max(end);
+            }
+            (covered >= block.end()).then_some(gene)
+        })
+        .collect()
+}
+
+/// Assigns a single record or a pair of mates
+fn assign_fragment(
+    records: &[&Record],
+    model: &GeneModel,
+    header: &HeaderView,
+    params: &CountParams,
+    blocks: &mut Vec<Block>,
+) -> Result<Assignment> {
+    if records.iter().all(|r| r.is_unmapped()) {
+        return Ok(Assignment::Unmapped);
+    }
+    if records
+        .iter()
+        .any(|r| !passes_record_predicates(r, &params.record_predicates))
+    {
+        return Ok(Assignment::Filtered);
+    }
+    if !params.multi_mapping && records.iter().any(|r| is_multi_mapping(r)) {
+        return Ok(Assignment::MultiMapping);
+    }
+    blocks.clear();
+    for record in records.iter().filter(|r| !r.is_unmapped()) {
+        model.push_blocks(record, header, blocks)?;
+    }
+    model.assign(blocks, params)
+}
+
+fn is_pairable(record: &Record) -> bool {
+    record.is_paired() && !record.is_unmapped() && !record.is_mate_unmapped()
+}
+
+fn run_count<W: Write>(
+    bam: &mut BamReader,
+    model: GeneModel,
+    params: &CountParams,
+    writer: W,
+) -> Result<CountSummary> {
+    // Set the number of threads for the BAM reader if necessary
+    if params.threads > 1 {
+        bam.set_threads(params.threads)?;
+    }
+
+    // Get the BAM header
+    let header = bam.header().clone();
+
+    let mut counts = vec![0; model.genes.len()];
+    let mut summary = CountSummary::default();
+    let mut blocks = Vec::new();
+
+    // Mates are keyed by name and their own and mate positions so that
+    // secondary alignments are only joined with their own mate
+    let mut mates: HashMap<(Vec<u8>, i64, i64), Record> = HashMap::new();
+
+    // Initialize an empty record to avoid repeated allocations of the BAM
+    let mut record = Record::new();
+
+    while let Some(result) = bam.read(&mut record) {
+        result?;
+
+        // Supplementary alignments are part of the primary alignment of the read
+        if record.is_supplementary() {
+            continue;
+        }
+
+        let assignment = if params.paired && is_pairable(&record) {
+            let mate_key = (record.qname().to_vec(), record.mpos(), record.pos());
+            if let Some(mate) = mates.remove(&mate_key) {
+                assign_fragment(&[&mate, &record], &model, &header, params, &mut blocks)?
+            } else {
+                let key = (record.qname().to_vec(), record.pos(), record.mpos());
+                mates.insert(key, record.clone());
+                continue;
+            }
+        } else {
+            assign_fragment(&[&record], &model, &header, params, &mut blocks)?
+        };
+        summary.add(assignment, &mut counts);
+    }
+
+    // Mates whose partner was never seen are counted on their own
+    for mate in mates.values() {
+        let assignment = assign_fragment(&[mate], &model, &header, params, &mut blocks)?;
+        summary.add(assignment, &mut counts);
+    }
+
+    // Write the counts table in the order genes appear in the GTF
+    let mut wtr = build_writer(writer);
+    for (gene, count) in model.genes.iter().zip(counts.iter()) {
+        wtr.serialize((gene, count))?;
+    }
+    wtr.flush()?;
+
+    Ok(summary)
+}
+
+/// Assigns each read (or read pair) to at most one gene of a GTF file
+/// and reports the number of reads assigned to each gene.
+///
+/// The GTF features are loaded into memory and the BAM is streamed,
+/// so the BAM file does not need to be sorted unless pairs are counted
+/// as fragments, in which case name-sorted input uses the least memory.
+pub fn count(args: CountArgs) -> Result<()> {
+    let (set, translater) = args.inputs.get_reader_gtf()?.gtf_set()?;
+    let translater = if let Some(translater) = translater {
+        translater
+    } else {
+        bail!("GTF file must be read with named fields");
+    };
+    let model = GeneModel::from_gtf(set, translater, &args.params)?;
+    let mut bam = args.inputs.get_reader_bam()?;
+    let writer = args.output.get_writer()?;
+    let summary = run_count(&mut bam, model, &args.params, writer)?;
+    if let Some(path) = args.params.summary {
+        let summary_writer = match_output(
+            Some(path),
+            args.output.compression_threads,
+            args.output.compression_level,
+        )?;
+        summary.write(summary_writer)?;
+    }
+    Ok(())
+}
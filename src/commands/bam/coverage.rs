@@ -1,17 +1,121 @@
-use super::utils::get_stranded_bed3;
+use super::{
+    depth::DepthProfile,
+    utils::{build_fetch_regions, get_stranded_bed3, passes_record_predicates},
+};
 use crate::{
     cli::bam::{BamCoverageArgs, BamCoverageParams},
     dispatch_single_with_htslib,
-    io::{write_depth_iter_with, WriteNamedIter, WriteNamedIterImpl},
+    io::{
+        build_writer, write_depth_iter_with, RegionBamReader, WriteNamedIter, WriteNamedIterImpl,
+    },
     types::{IntervalDepth, Rename, Renamer, SplitTranslater},
 };
 use anyhow::Result;
-use bedrs::{traits::IntervalBounds, IntervalContainer};
-use rust_htslib::bam::{Read, Reader as BamReader, Record};
+use bedrs::{traits::IntervalBounds, Coordinates, IntervalContainer, Strand};
 use serde::Serialize;
 
+/// Coverage statistics accumulated for a single interval
+#[derive(Default)]
+struct IntervalStats {
+    sense: usize,
+    antisense: usize,
+    depth_sum: usize,
+    profile: DepthProfile,
+}
+impl IntervalStats {
+    fn add_strand(&mut self, interval_strand: Option<Strand>, record_strand: Strand) {
+        match (interval_strand, record_strand) {
+            (Some(Strand::Forward), Strand::Forward) | (Some(Strand::Reverse), Strand::Reverse) => {
+                self.sense += 1
+            }
+            (Some(Strand::Forward), Strand::Reverse) | (Some(Strand::Reverse), Strand::Forward) => {
+                self.antisense += 1
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds the portion of a record that falls within the interval
+    fn add_span(
+        &mut self,
+        iv_start: usize,
+        iv_end: usize,
+        start: usize,
+        end: usize,
+        breadth: bool,
+    ) {
+        let start = start.max(iv_start);
+        let end = end.min(iv_end);
+        if start >= end {
+            return;
+        }
+        self.depth_sum += end - start;
+        if breadth {
+            self.profile.add(iv_start, iv_end, start, end);
+        }
+    }
+}
+
+/// The optional columns reported after the overlap count
+#[derive(Serialize)]
+struct CoverageColumns {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sense: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    antisense: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bases_covered: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fraction_covered: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mean_depth: Option<f64>,
+}
+impl CoverageColumns {
+    fn new(stats: &IntervalStats, length: usize, params: &BamCoverageParams) -> Self {
+        let (sense, antisense) = if params.strand_counts {
+            (Some(stats.sense), Some(stats.antisense))
+        } else {
+            (None, None)
+        };
+        let (bases_covered, breadth_length, fraction_covered) = if params.breadth {
+            let covered = stats.profile.bases_covered();
+            let fraction = if length > 0 {
+                covered as f64 / length as f64
+            } else {
+                0.0
+            };
+            (Some(covered), Some(length), Some(fraction))
+        } else {
+            (None, None, None)
+        };
+        let mean_depth = if params.mean_depth {
+            if length > 0 {
+                Some(stats.depth_sum as f64 / length as f64)
+            } else {
+                Some(0.0)
+            }
+        } else {
+            None
+        };
+        Self {
+            sense,
+            antisense,
+            bases_covered,
+            length: breadth_length,
+            fraction_covered,
+            mean_depth,
+        }
+    }
+}
+
+fn has_extra_columns(params: &BamCoverageParams) -> bool {
+    params.strand_counts || params.breadth || params.mean_depth
+}
+
 fn run_coverage<'a, I, N, W>(
-    bam: &mut BamReader,
+    bam: &mut RegionBamReader,
     mut set: IntervalContainer<I, usize, usize>,
     translater: Option<&'a SplitTranslater>,
     params: BamCoverageParams,
@@ -38,6 +142,14 @@ where
 
     let mut coverage = vec![0; set.len()];
 
+    // Only track the per-interval statistics if they will be reported
+    let extra_columns = has_extra_columns(&params);
+    let mut stats: Vec<IntervalStats> = if extra_columns {
+        (0..set.len()).map(|_| IntervalStats::default()).collect()
+    } else {
+        Vec::new()
+    };
+
     // Get the BAM header
     let header = bam.header().clone();
 
@@ -47,30 +159,51 @@ where
     // Initialize the overlap query method
     let query_method = params.overlap_predicates.into();
 
-    // Initialize an empty record to avoid repeated allocations of the BAM
-    let mut record = Record::new();
+    // Only fetch the records within the BED regions if the BAM is indexed
+    let regions = build_fetch_regions(&set, translater, &header)?;
 
     // Main loop over the BAM records
-    while let Some(result) = bam.read(&mut record) {
-        // exhaust the result
-        result?;
+    bam.for_each_record(Some(&regions), |record| {
+        // Skip records that do not meet the record predicates
+        if !passes_record_predicates(record, &params.record_predicates) {
+            return Ok(());
+        }
         // Get the stranded BED3 record
-        if let Some(bed) = get_stranded_bed3(&record, &header, translater)? {
+        if let Some(bed) = get_stranded_bed3(record, &header, translater)? {
             // Increment the coverage for each overlapping interval
-            for (idx, _ov) in set.query_iter_enumerate(&bed, query_method)? {
+            for (idx, ov) in set.query_iter_enumerate(&bed, query_method)? {
                 coverage[idx] += 1;
+                if extra_columns {
+                    let iv_stats = &mut stats[idx];
+                    let (start, end) = (bed.start(), bed.end());
+                    iv_stats.add_strand(ov.strand(), bed.strand().unwrap_or_default());
+                    iv_stats.add_span(ov.start(), ov.end(), start, end, params.breadth);
+                }
             }
         }
-    }
+        Ok(())
+    })?;
 
-    // Define an iterator over the depth and BED intervals
-    let depth_iter = set
-        .iter()
-        .zip(coverage.iter())
-        .map(|(iv, depth)| IntervalDepth::new(*iv, *depth, Some(translater)));
+    if !extra_columns {
+        // Define an iterator over the depth and BED intervals
+        let depth_iter = set
+            .iter()
+            .zip(coverage.iter())
+            .map(|(iv, depth)| IntervalDepth::new(*iv, *depth, Some(translater)));
 
-    // Write the depth iterator to the writer
-    write_depth_iter_with(depth_iter, writer, Some(translater))
+        // Write the depth iterator to the writer
+        return write_depth_iter_with(depth_iter, writer, Some(translater));
+    }
+
+    // Write the intervals with their depth and the requested statistics
+    let mut wtr = build_writer(writer);
+    for ((iv, depth), iv_stats) in set.iter().zip(coverage.iter()).zip(stats.iter()) {
+        let named: N = Renamer::rename_with(iv, translater);
+        let columns = CoverageColumns::new(iv_stats, iv.end() - iv.start(), &params);
+        wtr.serialize((named, depth, columns))?;
+    }
+    wtr.flush()?;
+    Ok(())
 }
 
 /// This function runs differently than the standalone coverage
@@ -85,6 +218,10 @@ where
 /// bam file and just increment coverage across all intervals
 /// that meet the overlap criteria. This will be more memory
 /// efficient than loading the entire BAM file into memory.
+///
+/// When breadth of coverage is requested a depth change array is
+/// allocated for each interval that is overlapped by at least one
+/// record, so memory scales with the total length of covered intervals.
 pub fn coverage(args: BamCoverageArgs) -> Result<()> {
     let bed_reader = args.inputs.get_reader_bed()?;
     let mut bam_reader = args.inputs.get_reader_bam()?;
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Tracks the changes in depth along a single chromosome
+///
+/// Each read adds one at its start and removes one at its end. Because the
+/// input is coordinate-sorted, all changes before the start of the current
+/// read are final and can be collapsed into runs of constant depth.
+#[derive(Default)]
+pub struct DepthTracker {
+    events: BTreeMap<usize, i64>,
+    depth: i64,
+    run_start: usize,
+}
+impl DepthTracker {
+    pub fn add(&mut self, start: usize, end: usize) {
+        *self.events.entry(start).or_insert(0) += 1;
+        *self.events.entry(end).or_insert(0) -= 1;
+    }
+
+    /// Collapses all changes before `pos` into runs of constant depth
+    pub fn flush_until<F>(&mut self, pos: usize, mut emit: F) -> Result<()>
+    where
+        F: FnMut(usize, usize, usize) -> Result<()>,
+    {
+        while let Some((&key, &delta)) = self.events.first_key_value() {
+            if key >= pos {
+                break;
+            }
+            self.events.pop_first();
+            if delta == 0 {
+                continue;
+            }
+            if key > self.run_start {
+                emit(self.run_start, key, self.depth as usize)?;
+            }
+            self.depth += delta;
+            self.run_start = key;
+        }
+        Ok(())
+    }
+
+    /// Collapses all remaining changes and closes the chromosome with a zero-depth run
+    pub fn finish<F>(&mut self, chr_len: usize, mut emit: F) -> Result<()>
+    where
+        F: FnMut(usize, usize, usize) -> Result<()>,
+    {
+        self.flush_until(usize::MAX, &mut emit)?;
+        if chr_len > self.run_start {
+            emit(self.run_start, chr_len, 0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Per-base depth along a single interval
+///
+/// The depth changes are stored relative to the interval start and are
+/// only allocated once a record overlaps the interval.
+#[derive(Default)]
+pub struct DepthProfile {
+    changes: Option<Vec<i64>>,
+}
+impl DepthProfile {
+    /// Adds the portion of a record that falls within the interval
+    pub fn add(&mut self, iv_start: usize, iv_end: usize, start: usize, end: usize) {
+        let start = start.max(iv_start);
+        let end = end.min(iv_end);
+        if start >= end {
+            return;
+        }
+        let changes = self
+            .changes
+            .get_or_insert_with(|| vec![0; iv_end - iv_start + 1]);
+        changes[start - iv_start] += 1;
+        changes[end - iv_start] -= 1;
+    }
+
+    /// The depth at each base of the interval (empty if no record overlapped it)
+    pub fn depths(&self) -> impl Iterator<Item = usize> + '_ {
+        self.changes
+            .iter()
+            .flat_map(|changes| changes[..changes.len() - 1].iter())
+            .scan(0, |depth, delta| {
+                *depth += delta;
+                Some(*depth as usize)
+            })
+    }
+
+    /// Number of bases within the interval covered by at least one record
+    pub fn bases_covered(&self) -> usize {
+        self.depths().filter(|depth| *depth > 0).count()
+    }
+}
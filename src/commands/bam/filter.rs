@@ -1,66 +1,52 @@
 use crate::{
-    cli::bam::{FilterArgs, FilterParams},
+    cli::{
+        bam::{FilterArgs, FilterParams},
+        BamOutput,
+    },
     dispatch_single_with_htslib,
-    io::{WriteNamedIter, WriteNamedIterImpl},
-    types::SplitTranslater,
+    io::{RegionBamReader, WriteNamedIter, WriteNamedIterImpl},
+    types::{Bed6Set, SplitTranslater, Translate},
 };
 
-use super::utils::get_stranded_bed3;
+use super::utils::{
+    build_fetch_regions, get_stranded_bed3, passes_record_predicates, read_named_set,
+};
 use anyhow::Result;
 use bedrs::{traits::IntervalBounds, types::Query, IntervalContainer};
-use rust_htslib::bam::{HeaderView, Read, Reader as BamReader, Record, Writer as BamWriter};
+use hashbrown::{hash_map::Entry, HashMap};
+use rust_htslib::bam::{HeaderView, Record, Writer as BamWriter};
 use serde::Serialize;
 
-fn run_inverted_overlap<I>(
-    record: &Record,
-    header: &HeaderView,
-    set: &IntervalContainer<I, usize, usize>,
-    translater: &SplitTranslater,
-    query_method: Query<usize>,
-    wtr: &mut BamWriter,
-) -> Result<()>
-where
-    I: IntervalBounds<usize, usize> + Copy + Serialize,
-    WriteNamedIterImpl: WriteNamedIter<I>,
-{
-    if let Some(bed) = get_stranded_bed3(record, header, translater)? {
-        let no_overlaps = set.query_iter(&bed, query_method)?.next().is_none();
-        if no_overlaps {
-            wtr.write(record)?;
-        }
-    } else {
-        wtr.write(record)?;
-    }
-    Ok(())
+/// The main output and the optional output for the records it does not receive
+struct FilterWriters {
+    primary: BamWriter,
+    complement: Option<BamWriter>,
 }
 
-fn run_overlap<I>(
+fn has_overlap<I>(
     record: &Record,
     header: &HeaderView,
     set: &IntervalContainer<I, usize, usize>,
     translater: &SplitTranslater,
     query_method: Query<usize>,
-    wtr: &mut BamWriter,
-) -> Result<()>
+) -> Result<bool>
 where
     I: IntervalBounds<usize, usize> + Copy + Serialize,
     WriteNamedIterImpl: WriteNamedIter<I>,
 {
     if let Some(bed) = get_stranded_bed3(record, header, translater)? {
-        let any_overlaps = set.query_iter(&bed, query_method)?.next().is_some();
-        if any_overlaps {
-            wtr.write(record)?;
-        }
+        Ok(set.query_iter(&bed, query_method)?.next().is_some())
+    } else {
+        Ok(false)
     }
-    Ok(())
 }
 
 fn run_filter<I>(
-    bam: &mut BamReader,
+    bam: &mut RegionBamReader,
     mut set: IntervalContainer<I, usize, usize>,
     translater: Option<&SplitTranslater>,
     params: FilterParams,
-    writer: &mut BamWriter,
+    writers: &mut FilterWriters,
 ) -> Result<()>
 where
     I: IntervalBounds<usize, usize> + Copy + Serialize,
@@ -78,31 +64,131 @@ where
     // Initialize the overlap query method
     let query_method = params.overlap_predicates.into();
 
-    // Initialize an empty record to avoid repeated allocations
-    let mut record = Record::new();
+    // Records outside of the BED regions are required when inverting or writing the complement
+    // so the full file is visited. Otherwise only the records within the BED regions are
+    // fetched if the BAM is indexed.
+    let invert = params.output_predicates.invert;
+    let regions = if invert || writers.complement.is_some() {
+        None
+    } else {
+        Some(build_fetch_regions(&set, translater, &header)?)
+    };
 
-    if params.output_predicates.invert {
-        while let Some(result) = bam.read(&mut record) {
-            result?;
-            run_inverted_overlap(&record, &header, &set, translater, query_method, writer)?;
+    bam.for_each_record(regions.as_deref(), |record| {
+        if !passes_record_predicates(record, &params.record_predicates) {
+            return Ok(());
         }
+        let overlaps = has_overlap(record, &header, &set, translater, query_method)?;
+        if overlaps != invert {
+            writers.primary.write(record)?;
+        } else if let Some(complement) = writers.complement.as_mut() {
+            complement.write(record)?;
+        }
+        Ok(())
+    })
+}
+
+/// Builds the output path of a BED name, replacing any path separators in the name
+fn split_path(prefix: &str, name: &str, output: &BamOutput) -> String {
+    let name = name.replace(['/', '\\'], "_");
+    format!("{}{}.{}", prefix, name, output.format.extension())
+}
+
+/// Routes the records overlapping each BED name into their own output file
+///
+/// Files are opened the first time a record overlaps an interval with their name,
+/// so names without any overlapping records do not create a file.
+fn run_split(
+    bam: &mut RegionBamReader,
+    mut set: Bed6Set,
+    translater: &SplitTranslater,
+    params: &FilterParams,
+    prefix: &str,
+    output: &BamOutput,
+    mut complement: Option<BamWriter>,
+) -> Result<()> {
+    // Get the header
+    let header = bam.header().clone();
+
+    // Sort the BED Set
+    set.sort();
+
+    // Initialize the overlap query method
+    let query_method = params.overlap_predicates.into();
+
+    // Only fetch the records within the BED regions if the complement is not required
+    let regions = if complement.is_some() {
+        None
     } else {
-        while let Some(result) = bam.read(&mut record) {
-            result?;
-            run_overlap(&record, &header, &set, translater, query_method, writer)?;
+        Some(build_fetch_regions(&set, translater, &header)?)
+    };
+
+    let mut writers: HashMap<usize, BamWriter> = HashMap::new();
+    let mut name_indices = Vec::new();
+
+    bam.for_each_record(regions.as_deref(), |record| {
+        if !passes_record_predicates(record, &params.record_predicates) {
+            return Ok(());
         }
-    }
-    Ok(())
+        name_indices.clear();
+        if let Some(bed) = get_stranded_bed3(record, &header, translater)? {
+            for iv in set.query_iter(&bed, query_method)? {
+                if !name_indices.contains(iv.name()) {
+                    name_indices.push(*iv.name());
+                }
+            }
+        }
+        if name_indices.is_empty() {
+            if let Some(complement) = complement.as_mut() {
+                complement.write(record)?;
+            }
+            return Ok(());
+        }
+        for name_idx in name_indices.iter() {
+            let writer = match writers.entry(*name_idx) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let name = translater.get_meta_name(*name_idx).unwrap_or_default();
+                    let path = split_path(prefix, name, output);
+                    entry.insert(output.get_writer_to(path, &header)?)
+                }
+            };
+            writer.write(record)?;
+        }
+        Ok(())
+    })
 }
 
-pub fn filter(args: FilterArgs) -> Result<()> {
+pub fn filter(mut args: FilterArgs) -> Result<()> {
+    args.output
+        .inherit_reference(args.inputs.reference.as_ref());
     let bed_reader = args.inputs.get_reader_bed()?;
     let mut bam_reader = args.inputs.get_reader_bam()?;
-    let mut writer = args.output.get_writer(bam_reader.header())?;
+    let complement = if let Some(path) = args.params.output_predicates.complement.clone() {
+        Some(args.output.get_writer_to(path, bam_reader.header())?)
+    } else {
+        None
+    };
+    if let Some(prefix) = args.params.output_predicates.split.clone() {
+        let (set, translater) = read_named_set(bed_reader)?;
+        return run_split(
+            &mut bam_reader,
+            set,
+            &translater,
+            &args.params,
+            &prefix,
+            &args.output,
+            complement,
+        );
+    }
+    let mut writers = FilterWriters {
+        primary: args.output.get_writer(bam_reader.header())?,
+        complement,
+    };
     dispatch_single_with_htslib!(
         &mut bam_reader,
         bed_reader,
-        &mut writer,
+        &mut writers,
         args.params,
         run_filter
     )
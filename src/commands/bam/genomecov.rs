@@ -0,0 +1,241 @@
+use super::{
+    depth::DepthTracker,
+    utils::{parse_blocks, parse_endpoints, passes_record_predicates},
+};
+use crate::{
+    cli::bam::{GenomecovArgs, GenomecovParams, GenomecovStrand},
+    io::build_writer,
+    types::NamedBedGraph,
+};
+use anyhow::{bail, Result};
+use rust_htslib::bam::{HeaderView, Read, Reader as BamReader, Record};
+use std::{io::Write, str::from_utf8};
+
+/// Writes depth runs as bedGraph records
+///
+/// When scaling to counts per million without knowing the total number of
+/// reads beforehand (i.e. when streaming from stdin) the total is only known
+/// at the end of the file, so runs are buffered until then.
+struct RunWriter<'a, W: Write> {
+    wtr: csv::Writer<W>,
+    header: &'a HeaderView,
+    zero: bool,
+    scale: f64,
+    buffer: Option<Vec<(u32, usize, usize, usize)>>,
+}
+impl<'a, W: Write> RunWriter<'a, W> {
+    fn new(
+        writer: W,
+        header: &'a HeaderView,
+        params: &GenomecovParams,
+        total_reads: Option<usize>,
+    ) -> Self {
+        let (scale, buffer) = match (params.cpm, total_reads) {
+            (true, Some(total_reads)) => (cpm_scale(total_reads), None),
+            (true, None) => (1.0, Some(Vec::new())),
+            (false, _) => (params.scale.unwrap_or(1.0), None),
+        };
+        Self {
+            wtr: build_writer(writer),
+            header,
+            zero: params.zero,
+            scale,
+            buffer,
+        }
+    }
+
+    fn serialize(&mut self, tid: u32, start: usize, end: usize, depth: usize) -> Result<()> {
+        let chr_name = from_utf8(self.header.tid2name(tid))?;
+        let score = depth as f64 * self.scale;
+        self.wtr
+            .serialize(NamedBedGraph::new(chr_name, start, end, score))?;
+        Ok(())
+    }
+
+    fn write_run(&mut self, tid: u32, start: usize, end: usize, depth: usize) -> Result<()> {
+        if depth == 0 && !self.zero {
+            return Ok(());
+        }
+        if let Some(buffer) = self.buffer.as_mut() {
+            buffer.push((tid, start, end, depth));
+            Ok(())
+        } else {
+            self.serialize(tid, start, end, depth)
+        }
+    }
+
+    fn write_empty_chromosome(&mut self, tid: u32) -> Result<()> {
+        let chr_len = self.header.target_len(tid).unwrap_or(0) as usize;
+        if chr_len > 0 {
+            self.write_run(tid, 0, chr_len, 0)?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, num_reads: usize) -> Result<()> {
+        if let Some(buffer) = self.buffer.take() {
+            self.scale = cpm_scale(num_reads);
+            for (tid, start, end, depth) in buffer {
+                self.serialize(tid, start, end, depth)?;
+            }
+        }
+        self.wtr.flush()?;
+        Ok(())
+    }
+}
+
+fn cpm_scale(num_reads: usize) -> f64 {
+    if num_reads > 0 {
+        1e6 / num_reads as f64
+    } else {
+        0.0
+    }
+}
+
+fn matches_strand(record: &Record, strand: Option<GenomecovStrand>) -> bool {
+    match strand {
+        Some(GenomecovStrand::Forward) => !record.is_reverse(),
+        Some(GenomecovStrand::Reverse) => record.is_reverse(),
+        None => true,
+    }
+}
+
+/// Checks whether a record contributes to the depth
+fn is_counted(record: &Record, params: &GenomecovParams) -> bool {
+    !record.is_unmapped()
+        && record.tid() >= 0
+        && passes_record_predicates(record, &params.record_predicates)
+        && matches_strand(record, params.strand)
+}
+
+/// Counts the reads contributing to the depth in a first pass over the file
+fn count_reads(bam: &mut BamReader, params: &GenomecovParams) -> Result<usize> {
+    if params.threads > 1 {
+        bam.set_threads(params.threads)?;
+    }
+    let mut record = Record::new();
+    let mut num_reads = 0;
+    while let Some(result) = bam.read(&mut record) {
+        result?;
+        if is_counted(&record, params) {
+            num_reads += 1;
+        }
+    }
+    Ok(num_reads)
+}
+
+fn finish_chromosome<W: Write>(
+    tracker: &mut DepthTracker,
+    tid: u32,
+    runs: &mut RunWriter<W>,
+) -> Result<()> {
+    let chr_len = runs.header.target_len(tid).unwrap_or(0) as usize;
+    tracker.finish(chr_len, |start, end, depth| {
+        runs.write_run(tid, start, end, depth)
+    })
+}
+
+fn run_genomecov<W: Write>(
+    bam: &mut BamReader,
+    params: GenomecovParams,
+    total_reads: Option<usize>,
+    writer: W,
+) -> Result<()> {
+    // Set the number of threads for the BAM reader if necessary
+    if params.threads > 1 {
+        bam.set_threads(params.threads)?;
+    }
+
+    // Get the BAM header
+    let header = bam.header().clone();
+
+    let mut runs = RunWriter::new(writer, &header, &params, total_reads);
+    let mut tracker = DepthTracker::default();
+    let mut current_tid: Option<u32> = None;
+    let mut last_pos = 0;
+    let mut num_reads = 0;
+
+    // Initialize an empty record to avoid repeated allocations of the BAM
+    let mut record = Record::new();
+
+    while let Some(result) = bam.read(&mut record) {
+        result?;
+        if !is_counted(&record, &params) {
+            continue;
+        }
+
+        let tid = record.tid() as u32;
+        let pos = record.pos() as usize;
+        match current_tid {
+            Some(current) if current == tid => {
+                if pos < last_pos {
+                    bail!("BAM records must be coordinate sorted to calculate genome coverage");
+                }
+            }
+            Some(current) if current > tid => {
+                bail!("BAM records must be coordinate sorted to calculate genome coverage");
+            }
+            _ => {
+                // Close the previous chromosome and any chromosomes without reads
+                let first_empty = if let Some(current) = current_tid {
+                    finish_chromosome(&mut tracker, current, &mut runs)?;
+                    current + 1
+                } else {
+                    0
+                };
+                for empty in first_empty..tid {
+                    runs.write_empty_chromosome(empty)?;
+                }
+                tracker = DepthTracker::default();
+                current_tid = Some(tid);
+            }
+        }
+        last_pos = pos;
+
+        // All changes before the current read are final
+        tracker.flush_until(pos, |start, end, depth| {
+            runs.write_run(tid, start, end, depth)
+        })?;
+
+        if params.split {
+            for (start, end) in parse_blocks(&record, Some(1)) {
+                tracker.add(start, end);
+            }
+        } else {
+            let (start, end) = parse_endpoints(&record)?;
+            tracker.add(start, end);
+        }
+        num_reads += 1;
+    }
+
+    // Close the last chromosome and any trailing chromosomes without reads
+    let first_empty = if let Some(current) = current_tid {
+        finish_chromosome(&mut tracker, current, &mut runs)?;
+        current + 1
+    } else {
+        0
+    };
+    for empty in first_empty..header.target_count() {
+        runs.write_empty_chromosome(empty)?;
+    }
+
+    runs.finish(num_reads)
+}
+
+/// Streams a coordinate-sorted BAM file and reports the per-base depth
+/// as run-length encoded bedGraph records.
+///
+/// Only the depth changes of reads that overlap the current position are
+/// kept in memory, so this scales to whole-genome BAM files. Scaling to
+/// counts per million counts the reads in a first pass over the file, or
+/// buffers the runs until all reads are seen when streaming from stdin.
+pub fn genomecov(args: GenomecovArgs) -> Result<()> {
+    let total_reads = if args.params.cpm && args.input.input.is_some() {
+        Some(count_reads(&mut args.input.get_reader()?, &args.params)?)
+    } else {
+        None
+    };
+    let mut bam = args.input.get_reader()?;
+    let writer = args.output.get_writer()?;
+    run_genomecov(&mut bam, args.params, total_reads, writer)
+}
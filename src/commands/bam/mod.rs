@@ -1,8 +1,17 @@
+mod annotate;
 mod convert;
+mod count;
 mod coverage;
+mod depth;
 mod filter;
+mod genomecov;
+mod stats;
 pub mod utils;
 
+pub use annotate::annotate;
 pub use convert::convert;
+pub use count::count;
 pub use coverage::coverage;
 pub use filter::filter;
+pub use genomecov::genomecov;
+pub use stats::stats;
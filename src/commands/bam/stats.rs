@@ -0,0 +1,376 @@
+use super::{
+    depth::{DepthProfile, DepthTracker},
+    utils::{build_fetch_regions, get_stranded_bed3, parse_endpoints, passes_record_predicates},
+};
+use crate::{
+    cli::bam::{StatsArgs, StatsParams},
+    dispatch_single_with_htslib,
+    io::{
+        build_writer, match_output, BedReader, RegionBamReader, WriteNamedIter, WriteNamedIterImpl,
+    },
+    types::{FieldFormat, Rename, Renamer, SplitTranslater},
+};
+use anyhow::{bail, Result};
+use bedrs::{traits::IntervalBounds, types::Query, IntervalContainer};
+use rust_htslib::bam::{Read, Reader as BamReader, Record};
+use serde::Serialize;
+use std::io::Write;
+
+/// Inclusive upper bounds and labels of the MAPQ histogram bins
+const MAPQ_BINS: [(u8, &str); 8] = [
+    (0, "0"),
+    (9, "1-9"),
+    (19, "10-19"),
+    (29, "20-29"),
+    (39, "30-39"),
+    (59, "40-59"),
+    (254, "60-254"),
+    (255, "255"),
+];
+
+/// Statistics accumulated over the records of a single region
+#[derive(Default)]
+struct RegionStats {
+    reads: usize,
+    duplicates: usize,
+    inserts: Vec<i64>,
+    mapq: [usize; 8],
+    depth_sum: f64,
+    depth_sq_sum: f64,
+    length: usize,
+}
+impl RegionStats {
+    fn add_record(&mut self, record: &Record) {
+        self.reads += 1;
+        if record.is_duplicate() {
+            self.duplicates += 1;
+        }
+        let bin = MAPQ_BINS
+            .iter()
+            .position(|(max, _)| record.mapq() <= *max)
+            .unwrap_or(MAPQ_BINS.len() - 1);
+        self.mapq[bin] += 1;
+
+        // Each proper pair is only measured once by its leftmost mate
+        if record.is_proper_pair()
+            && !record.is_secondary()
+            && !record.is_supplementary()
+            && record.insert_size() > 0
+        {
+            self.inserts.push(record.insert_size());
+        }
+    }
+
+    /// Adds a run of bases with the same depth
+    fn add_depth_run(&mut self, depth: usize, length: usize) {
+        let depth = depth as f64;
+        self.depth_sum += depth * length as f64;
+        self.depth_sq_sum += depth * depth * length as f64;
+    }
+
+    fn finish(mut self) -> StatsRow {
+        let proper_pairs = self.inserts.len();
+        let insert_mean = if proper_pairs > 0 {
+            self.inserts.iter().sum::<i64>() as f64 / proper_pairs as f64
+        } else {
+            0.0
+        };
+        self.inserts.sort_unstable();
+        let insert_median = match proper_pairs {
+            0 => 0.0,
+            n if n % 2 == 0 => (self.inserts[n / 2 - 1] + self.inserts[n / 2]) as f64 / 2.0,
+            n => self.inserts[n / 2] as f64,
+        };
+        let duplicate_fraction = if self.reads > 0 {
+            self.duplicates as f64 / self.reads as f64
+        } else {
+            0.0
+        };
+        let (mean_depth, depth_cv) = if self.length > 0 && self.depth_sum > 0.0 {
+            let mean = self.depth_sum / self.length as f64;
+            let variance = (self.depth_sq_sum / self.length as f64 - mean * mean).max(0.0);
+            (mean, variance.sqrt() / mean)
+        } else {
+            (0.0, 0.0)
+        };
+        StatsRow {
+            reads: self.reads,
+            proper_pairs,
+            insert_mean,
+            insert_median,
+            duplicate_fraction,
+            mean_depth,
+            depth_cv,
+            mapq: self.mapq,
+        }
+    }
+}
+
+/// The reported statistics of a single region
+#[derive(Serialize)]
+struct StatsRow {
+    reads: usize,
+    proper_pairs: usize,
+    insert_mean: f64,
+    insert_median: f64,
+    duplicate_fraction: f64,
+    mean_depth: f64,
+    depth_cv: f64,
+    mapq: [usize; 8],
+}
+impl StatsRow {
+    fn write_json<W: Write>(&self, chr: &str, start: usize, end: usize, wtr: &mut W) -> Result<()> {
+        write!(
+            wtr,
+            "{{\"chr\":{},\"start\":{},\"end\":{},\"reads\":{},\"proper_pairs\":{},\
+             \"insert_mean\":{},\"insert_median\":{},\"duplicate_fraction\":{},\
+             \"mean_depth\":{},\"depth_cv\":{},\"mapq_histogram\":{{",
+            json_string(chr),
+            start,
+            end,
+            self.reads,
+            self.proper_pairs,
+            self.insert_mean,
+            self.insert_median,
+            self.duplicate_fraction,
+            self.mean_depth,
+            self.depth_cv,
+        )?;
+        for (idx, ((_, label), count)) in MAPQ_BINS.iter().zip(self.mapq.iter()).enumerate() {
+            if idx > 0 {
+                write!(wtr, ",")?;
+            }
+            write!(wtr, "\"{}\":{}", label, count)?;
+        }
+        write!(wtr, "}}}}")?;
+        Ok(())
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Writes the rows as a JSON array of objects
+fn write_json<'a, I>(path: String, rows: I) -> Result<()>
+where
+    I: Iterator<Item = (&'a str, usize, usize, &'a StatsRow)>,
+{
+    let mut wtr = match_output(Some(path), 1, 6)?;
+    write!(wtr, "[")?;
+    for (idx, (chr, start, end, row)) in rows.enumerate() {
+        if idx > 0 {
+            write!(wtr, ",")?;
+        }
+        write!(wtr, "\n  ")?;
+        row.write_json(chr, start, end, &mut wtr)?;
+    }
+    writeln!(wtr, "\n]")?;
+    wtr.flush()?;
+    Ok(())
+}
+
+fn run_region_stats<'a, I, N, W>(
+    bam: &mut RegionBamReader,
+    mut set: IntervalContainer<I, usize, usize>,
+    translater: Option<&'a SplitTranslater>,
+    params: StatsParams,
+    writer: &mut W,
+) -> Result<()>
+where
+    I: IntervalBounds<usize, usize> + Copy + Serialize,
+    N: IntervalBounds<&'a str, usize> + Serialize,
+    W: Write,
+    WriteNamedIterImpl: WriteNamedIter<I>,
+    Renamer: Rename<'a, I, N>,
+{
+    // Sort the BED set intervals
+    set.sort();
+
+    // Set the number of threads for the BAM reader if necessary
+    if params.threads > 1 {
+        bam.set_threads(params.threads)?;
+    }
+
+    // Get the BAM header
+    let header = bam.header().clone();
+
+    // Export the translater (should always be Some)
+    let translater = translater.unwrap();
+
+    let mut stats = Vec::with_capacity(set.len());
+    stats.resize_with(set.len(), RegionStats::default);
+    let mut profiles = Vec::with_capacity(set.len());
+    profiles.resize_with(set.len(), DepthProfile::default);
+
+    // Only fetch the records within the BED regions if the BAM is indexed
+    let regions = build_fetch_regions(&set, translater, &header)?;
+
+    bam.for_each_record(Some(&regions), |record| {
+        if record.is_unmapped()
+            || record.tid() < 0
+            || !passes_record_predicates(record, &params.record_predicates)
+        {
+            return Ok(());
+        }
+        if let Some(bed) = get_stranded_bed3(record, &header, translater)? {
+            let (start, end) = parse_endpoints(record)?;
+            for (idx, iv) in set.query_iter_enumerate(&bed, Query::default())? {
+                stats[idx].add_record(record);
+                profiles[idx].add(iv.start(), iv.end(), start, end);
+            }
+        }
+        Ok(())
+    })?;
+
+    // Summarize the depth of each interval including its uncovered bases
+    let rows = set
+        .iter()
+        .zip(stats)
+        .zip(profiles.iter())
+        .map(|((iv, mut iv_stats), profile)| {
+            iv_stats.length = iv.end() - iv.start();
+            profile
+                .depths()
+                .for_each(|depth| iv_stats.add_depth_run(depth, 1));
+            let named: N = Renamer::rename_with(iv, translater);
+            (named, iv_stats.finish())
+        })
+        .collect::<Vec<_>>();
+
+    let mut wtr = build_writer(writer);
+    for (named, row) in rows.iter() {
+        wtr.serialize((named, row))?;
+    }
+    wtr.flush()?;
+
+    if let Some(path) = params.json {
+        let json_rows = rows
+            .iter()
+            .map(|(named, row)| (*named.chr(), named.start(), named.end(), row));
+        write_json(path, json_rows)?;
+    }
+    Ok(())
+}
+
+/// Label of the single row reported without a BED file
+const GENOME_LABEL: &str = "genome";
+
+const UNSORTED_ERROR: &str =
+    "BAM records must be coordinate sorted to calculate genome-wide statistics";
+
+fn run_genome_stats<W: Write>(bam: &mut BamReader, params: StatsParams, writer: W) -> Result<()> {
+    // Set the number of threads for the BAM reader if necessary
+    if params.threads > 1 {
+        bam.set_threads(params.threads)?;
+    }
+
+    // Get the BAM header
+    let header = bam.header().clone();
+
+    let mut stats = RegionStats {
+        length: (0..header.target_count())
+            .map(|tid| header.target_len(tid).unwrap_or(0) as usize)
+            .sum(),
+        ..Default::default()
+    };
+    let mut tracker = DepthTracker::default();
+    let mut current_tid: Option<u32> = None;
+    let mut last_pos = 0;
+
+    // Initialize an empty record to avoid repeated allocations of the BAM
+    let mut record = Record::new();
+
+    while let Some(result) = bam.read(&mut record) {
+        result?;
+        if record.is_unmapped()
+            || record.tid() < 0
+            || !passes_record_predicates(&record, &params.record_predicates)
+        {
+            continue;
+        }
+
+        let tid = record.tid() as u32;
+        let pos = record.pos() as usize;
+        match current_tid {
+            Some(current) if current == tid => {
+                if pos < last_pos {
+                    bail!(UNSORTED_ERROR);
+                }
+            }
+            Some(current) if current > tid => {
+                bail!(UNSORTED_ERROR);
+            }
+            _ => {
+                tracker.flush_until(usize::MAX, |start, end, depth| {
+                    stats.add_depth_run(depth, end - start);
+                    Ok(())
+                })?;
+                tracker = DepthTracker::default();
+                current_tid = Some(tid);
+            }
+        }
+        last_pos = pos;
+
+        // All changes before the current read are final
+        tracker.flush_until(pos, |start, end, depth| {
+            stats.add_depth_run(depth, end - start);
+            Ok(())
+        })?;
+
+        let (start, end) = parse_endpoints(&record)?;
+        tracker.add(start, end);
+        stats.add_record(&record);
+    }
+    tracker.flush_until(usize::MAX, |start, end, depth| {
+        stats.add_depth_run(depth, end - start);
+        Ok(())
+    })?;
+
+    let length = stats.length;
+    let row = stats.finish();
+    let mut wtr = build_writer(writer);
+    wtr.serialize((GENOME_LABEL, 0, length, &row))?;
+    wtr.flush()?;
+
+    if let Some(path) = params.json {
+        write_json(path, std::iter::once((GENOME_LABEL, 0, length, &row)))?;
+    }
+    Ok(())
+}
+
+/// Reports quality control statistics of the BAM records within each
+/// BED interval, or across the whole genome if no BED file is provided.
+///
+/// Like the BAM coverage command the BED intervals are kept in memory
+/// and the BAM is streamed. The per-base depth of an interval is only
+/// allocated once a record overlaps it.
+pub fn stats(args: StatsArgs) -> Result<()> {
+    let mut writer = args.output.get_writer()?;
+    if let Some(bed) = args.params.bed.clone() {
+        // The bed format must always be read as string-based when working with BAM files
+        let bed_reader = BedReader::from_path(Some(bed), None, Some(FieldFormat::StringBased))?;
+        let mut bam_reader = args.input.get_region_reader()?;
+        dispatch_single_with_htslib!(
+            &mut bam_reader,
+            bed_reader,
+            &mut writer,
+            args.params,
+            run_region_stats
+        )
+    } else {
+        let mut bam_reader = args.input.get_reader()?;
+        run_genome_stats(&mut bam_reader, args.params, writer)
+    }
+}
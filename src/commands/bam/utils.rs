@@ -1,8 +1,15 @@
 use anyhow::{bail, Result};
-use bedrs::{Strand, StrandedBed3};
-use rust_htslib::bam::{HeaderView, Record};
+use bedrs::{traits::IntervalBounds, IntervalContainer, Strand, StrandedBed3};
+use rust_htslib::bam::{
+    record::{Aux, Cigar},
+    HeaderView, Record,
+};
 
-use crate::types::SplitTranslater;
+use crate::{
+    cli::bam::{ExtraColumn, RecordPredicates},
+    io::BedReader,
+    types::{Bed6Set, InputFormat, SplitTranslater, Translate},
+};
 
 pub fn parse_chr_name<'a>(record: &Record, header: &'a HeaderView) -> Result<&'a [u8]> {
     let tid = record.tid();
@@ -19,6 +26,82 @@ pub fn parse_endpoints(record: &Record) -> Result<(usize, usize)> {
     Ok((start, end))
 }
 
+/// Number of reported alignments of the read from the `NH` tag if present
+pub fn parse_num_hits(record: &Record) -> Option<i64> {
+    match record.aux(b"NH") {
+        Ok(Aux::U8(n)) => Some(n as i64),
+        Ok(Aux::U16(n)) => Some(n as i64),
+        Ok(Aux::U32(n)) => Some(n as i64),
+        Ok(Aux::I8(n)) => Some(n as i64),
+        Ok(Aux::I16(n)) => Some(n as i64),
+        Ok(Aux::I32(n)) => Some(n as i64),
+        _ => None,
+    }
+}
+
+/// Whether the read aligns to multiple locations
+pub fn is_multi_mapping(record: &Record) -> bool {
+    record.is_secondary() || parse_num_hits(record).is_some_and(|n| n > 1)
+}
+
+/// Number of bases aligned to the reference (M/=/X)
+pub fn parse_aligned_length(record: &Record) -> usize {
+    record
+        .cigar()
+        .iter()
+        .map(|op| match op {
+            Cigar::Match(len) | Cigar::Equal(len) | Cigar::Diff(len) => *len as usize,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Checks whether a record meets all of the provided record predicates
+pub fn passes_record_predicates(record: &Record, predicates: &RecordPredicates) -> bool {
+    let flags = record.flags();
+    record.mapq() >= predicates.min_mapq
+        && flags & predicates.require_flags == predicates.require_flags
+        && flags & predicates.exclude_flags == 0
+        && (!predicates.proper_pair || record.is_proper_pair())
+        && (predicates.min_aligned_length == 0
+            || parse_aligned_length(record) >= predicates.min_aligned_length)
+}
+
+/// Splits the aligned span of a record into its contiguous reference blocks
+///
+/// Blocks are broken on reference skips (`N`) and on deletions (`D`) at least
+/// as long as `min_deletion` if provided. Shorter deletions are absorbed into
+/// the surrounding block. Returns the absolute `(start, end)` of each block.
+pub fn parse_blocks(record: &Record, min_deletion: Option<usize>) -> Vec<(usize, usize)> {
+    let mut blocks = Vec::new();
+    let mut pos = record.pos() as usize;
+    let mut block_start = pos;
+    let splits_block = |len: usize| min_deletion.is_some_and(|min| len >= min);
+    for op in record.cigar().iter() {
+        let len = op.len() as usize;
+        match op {
+            Cigar::Match(_) | Cigar::Equal(_) | Cigar::Diff(_) => {
+                pos += len;
+            }
+            Cigar::Del(_) if !splits_block(len) => {
+                pos += len;
+            }
+            Cigar::Del(_) | Cigar::RefSkip(_) => {
+                if pos > block_start {
+                    blocks.push((block_start, pos));
+                }
+                pos += len;
+                block_start = pos;
+            }
+            Cigar::Ins(_) | Cigar::SoftClip(_) | Cigar::HardClip(_) | Cigar::Pad(_) => {}
+        }
+    }
+    if pos > block_start {
+        blocks.push((block_start, pos));
+    }
+    blocks
+}
+
 const FIRST_SEGMENT: &[u8] = &[b'/', b'1'];
 const LAST_SEGMENT: &[u8] = &[b'/', b'2'];
 pub fn parse_query_name(record: &Record) -> Result<Vec<u8>> {
@@ -70,3 +153,197 @@ pub fn get_stranded_bed3(
     let strand = parse_strand(record);
     Ok(Some(StrandedBed3::new(chr_idx, start, end, strand)))
 }
+
+/// Builds the sorted and merged `(tid, start, end)` regions of a BED set for indexed queries
+///
+/// Assumes the set is already sorted. Chromosomes missing from the BAM header are skipped.
+pub fn build_fetch_regions<I>(
+    set: &IntervalContainer<I, usize, usize>,
+    translater: &SplitTranslater,
+    header: &HeaderView,
+) -> Result<Vec<(u32, usize, usize)>>
+where
+    I: IntervalBounds<usize, usize>,
+{
+    let merged = set.merge()?;
+    let mut regions = merged
+        .records()
+        .iter()
+        .filter_map(|iv| {
+            let chr_name = translater.get_chr_name(*iv.chr())?;
+            let tid = header.tid(chr_name.as_bytes())?;
+            Some((tid, iv.start(), iv.end()))
+        })
+        .collect::<Vec<_>>();
+    regions.sort_unstable();
+    Ok(regions)
+}
+
+/// Formats the value of an aux tag as it would appear in SAM (without the tag and type)
+pub fn format_aux(aux: &Aux) -> String {
+    fn join<T: ToString>(values: impl Iterator<Item = T>) -> String {
+        values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+    }
+    match aux {
+        Aux::Char(c) => (*c as char).to_string(),
+        Aux::I8(v) => v.to_string(),
+        Aux::U8(v) => v.to_string(),
+        Aux::I16(v) => v.to_string(),
+        Aux::U16(v) => v.to_string(),
+        Aux::I32(v) => v.to_string(),
+        Aux::U32(v) => v.to_string(),
+        Aux::Float(v) => v.to_string(),
+        Aux::Double(v) => v.to_string(),
+        Aux::String(v) | Aux::HexByteArray(v) => v.to_string(),
+        Aux::ArrayI8(array) => join(array.iter()),
+        Aux::ArrayU8(array) => join(array.iter()),
+        Aux::ArrayI16(array) => join(array.iter()),
+        Aux::ArrayU16(array) => join(array.iter()),
+        Aux::ArrayI32(array) => join(array.iter()),
+        Aux::ArrayU32(array) => join(array.iter()),
+        Aux::ArrayFloat(array) => join(array.iter()),
+    }
+}
+
+/// Fills the buffer with the requested extra columns of a record
+pub fn parse_extra_columns(
+    record: &Record,
+    header: &HeaderView,
+    columns: &[ExtraColumn],
+    missing: &str,
+    buffer: &mut Vec<String>,
+) -> Result<()> {
+    buffer.clear();
+    for column in columns {
+        let value = match column {
+            ExtraColumn::Tag(tag) => match record.aux(tag) {
+                Ok(aux) => format_aux(&aux),
+                Err(_) => missing.to_string(),
+            },
+            ExtraColumn::Flag => record.flags().to_string(),
+            ExtraColumn::Tlen => record.insert_size().to_string(),
+            ExtraColumn::MateChr => {
+                if record.mtid() < 0 {
+                    missing.to_string()
+                } else {
+                    std::str::from_utf8(header.tid2name(record.mtid() as u32))?.to_string()
+                }
+            }
+            ExtraColumn::MatePos => {
+                if record.mpos() < 0 {
+                    missing.to_string()
+                } else {
+                    record.mpos().to_string()
+                }
+            }
+        };
+        buffer.push(value);
+    }
+    Ok(())
+}
+
+/// Reads the named intervals of the BED file as BED6 so that every
+/// supported format shares the same name and strand fields
+pub fn read_named_set(bed_reader: BedReader) -> Result<(Bed6Set, SplitTranslater)> {
+    match bed_reader.input_format() {
+        InputFormat::Bed4
+        | InputFormat::Bed6
+        | InputFormat::Bed12
+        | InputFormat::NarrowPeak
+        | InputFormat::BroadPeak => bed_reader.bed6_set_named(),
+        format => bail!(
+            "Named intervals (BED4, BED6, or BED12) are required but found {:?}",
+            format
+        ),
+    }
+}
+
+#[cfg(test)]
+mod testing {
+
+    use super::*;
+    use rust_htslib::bam::record::CigarString;
+
+    fn build_record(pos: i64, cigar: Vec<Cigar>) -> Record {
+        let mut record = Record::new();
+        let cigar = CigarString(cigar);
+        let len = cigar
+            .iter()
+            .filter(|c| matches!(c, Cigar::Match(_) | Cigar::Ins(_)))
+            .map(|c| c.len() as usize)
+            .sum::<usize>();
+        record.set(b"read", Some(&cigar), &vec![b'A'; len], &vec![30; len]);
+        record.set_pos(pos);
+        record
+    }
+
+    #[test]
+    fn test_parse_blocks_unspliced() {
+        let record = build_record(100, vec![Cigar::SoftClip(5), Cigar::Match(50)]);
+        let blocks = parse_blocks(&record, None);
+        assert_eq!(blocks, vec![(100, 150)]);
+    }
+
+    #[test]
+    fn test_parse_blocks_spliced() {
+        let record = build_record(
+            100,
+            vec![Cigar::Match(10), Cigar::RefSkip(100), Cigar::Match(20)],
+        );
+        let blocks = parse_blocks(&record, None);
+        assert_eq!(blocks, vec![(100, 110), (210, 230)]);
+    }
+
+    #[test]
+    fn test_parse_blocks_deletions() {
+        let record = build_record(
+            100,
+            vec![
+                Cigar::Match(10),
+                Cigar::Del(2),
+                Cigar::Match(10),
+                Cigar::Del(20),
+                Cigar::Match(10),
+            ],
+        );
+        assert_eq!(parse_blocks(&record, None), vec![(100, 152)]);
+        assert_eq!(
+            parse_blocks(&record, Some(10)),
+            vec![(100, 122), (142, 152)]
+        );
+    }
+
+    #[test]
+    fn test_parse_blocks_flanking_gaps() {
+        let record = build_record(
+            100,
+            vec![
+                Cigar::Del(20),
+                Cigar::Match(10),
+                Cigar::RefSkip(50),
+                Cigar::Match(10),
+                Cigar::Del(20),
+            ],
+        );
+        assert_eq!(
+            parse_blocks(&record, Some(10)),
+            vec![(120, 130), (180, 190)]
+        );
+        assert_eq!(parse_blocks(&record, None), vec![(100, 130), (180, 210)]);
+
+        let record = build_record(
+            100,
+            vec![Cigar::RefSkip(5), Cigar::Match(10), Cigar::RefSkip(5)],
+        );
+        assert_eq!(parse_blocks(&record, None), vec![(105, 115)]);
+    }
+
+    #[test]
+    fn test_format_aux() {
+        assert_eq!(format_aux(&Aux::String("ACGT-1")), "ACGT-1");
+        assert_eq!(format_aux(&Aux::I32(-3)), "-3");
+        assert_eq!(format_aux(&Aux::Char(b'A')), "A");
+        let values = [1u8, 2, 3];
+        assert_eq!(format_aux(&Aux::ArrayU8((&values).into())), "1,2,3");
+    }
+}
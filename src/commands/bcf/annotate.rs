@@ -0,0 +1,199 @@
+use super::utils::parse_variant_spans;
+use crate::{
+    cli::bcf::{AnnotateArgs, AnnotateMode, AnnotateParams},
+    io::BedReader,
+    types::{Bed6Set, InputFormat, NumericBed3, SplitTranslater, Translate},
+};
+use anyhow::{bail, Result};
+use rust_htslib::bcf::{
+    header::{HeaderView, TagLength, TagType},
+    Header, Read as VcfRead, Reader as VcfReader, Writer as VcfWriter,
+};
+
+/// Reads the BED intervals as BED6 so that names and scores share the same fields
+///
+/// BED3 intervals can only be used for flags and bedGraph intervals have no
+/// name so they can only be used for scores or flags.
+fn read_annotation_set(
+    bed_reader: BedReader,
+    mode: AnnotateMode,
+) -> Result<(Bed6Set, SplitTranslater)> {
+    match (bed_reader.input_format(), mode) {
+        (InputFormat::Bed3, AnnotateMode::Name | AnnotateMode::Score) => {
+            bail!("BED3 intervals have no names or scores, use the flag mode instead")
+        }
+        (InputFormat::BedGraph, AnnotateMode::Name) => {
+            bail!("bedGraph intervals have no names, use the score or flag mode instead")
+        }
+        _ => bed_reader.bed6_set_named(),
+    }
+}
+
+/// Formats the number of values of an INFO key as in the VCF header
+fn format_tag_length(length: TagLength) -> String {
+    match length {
+        TagLength::Fixed(n) => n.to_string(),
+        TagLength::AltAlleles => "A".to_string(),
+        TagLength::Alleles => "R".to_string(),
+        TagLength::Genotypes => "G".to_string(),
+        TagLength::Variable => ".".to_string(),
+    }
+}
+
+/// Builds the output header with the `##INFO` line of the annotation key
+///
+/// A key already defined in the input is reused if its Number and Type match the mode.
+fn build_header(input: &HeaderView, params: &AnnotateParams, source: &str) -> Result<Header> {
+    let (number, tag_length, tag_type, type_name, description) = match params.mode {
+        AnnotateMode::Name => (
+            ".",
+            TagLength::Variable,
+            TagType::String,
+            "String",
+            "Names of the overlapping intervals",
+        ),
+        AnnotateMode::Score => (
+            ".",
+            TagLength::Variable,
+            TagType::Float,
+            "Float",
+            "Scores of the overlapping intervals",
+        ),
+        AnnotateMode::Flag => (
+            "0",
+            TagLength::Fixed(0),
+            TagType::Flag,
+            "Flag",
+            "Overlaps an interval",
+        ),
+    };
+    let mut header = Header::from_template(input);
+    match input.info_type(params.key.as_bytes()) {
+        Ok((existing_type, existing_length))
+            if existing_type == tag_type && existing_length == tag_length => {}
+        Ok((existing_type, existing_length)) => bail!(
+            "INFO key {} is already defined with Number={} and Type={:?} (expected Number={} and Type={})",
+            params.key,
+            format_tag_length(existing_length),
+            existing_type,
+            number,
+            type_name
+        ),
+        Err(_) => {
+            let line = format!(
+                "##INFO=<ID={},Number={},Type={},Description=\"{} in {}\">",
+                params.key, number, type_name, description, source
+            );
+            header.push_record(line.as_bytes());
+        }
+    }
+    Ok(header)
+}
+
+/// Replaces the characters that are reserved within INFO values
+fn sanitize_name(name: &str) -> String {
+    name.replace([',', ';', '=', ' ', '\t'], "_")
+}
+
+fn run_annotate(
+    vcf: &mut VcfReader,
+    mut set: Bed6Set,
+    translater: &SplitTranslater,
+    params: AnnotateParams,
+    writer: &mut VcfWriter,
+) -> Result<()> {
+    // Get the header
+    let header = vcf.header().clone();
+
+    // Sort the BED Set
+    set.sort();
+
+    // Initialize the overlap query method
+    let query_method = params.overlap_predicates.into();
+
+    let key = params.key.as_bytes();
+
+    // Reusable buffers of the overlapping interval indices and their values
+    let mut hits: Vec<usize> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    let mut scores: Vec<f32> = Vec::new();
+
+    // Initialize an empty VCF record to avoid repeated allocations
+    let mut record = vcf.empty_record();
+
+    while let Some(record_result) = vcf.read(&mut record) {
+        record_result?;
+
+        // Intervals overlapping multiple spans of a breakend are only reported once
+        hits.clear();
+        for (chr_bytes, start, end) in parse_variant_spans(&record, &header, &params.span_policy)? {
+            let chr_name = std::str::from_utf8(chr_bytes)?;
+            if let Some(chr_idx) = translater.get_chr_idx(chr_name) {
+                let query = NumericBed3::new(chr_idx, start, end);
+                for (idx, _) in set.query_iter_enumerate(&query, query_method)? {
+                    if !hits.contains(&idx) {
+                        hits.push(idx);
+                    }
+                }
+            }
+        }
+
+        // Move the record to the output header which defines the annotation key
+        writer.translate(&mut record);
+        match params.mode {
+            AnnotateMode::Name => {
+                names.clear();
+                for idx in hits.iter() {
+                    let name_idx = *set.records()[*idx].name();
+                    let name = sanitize_name(translater.get_meta_name(name_idx).unwrap_or("."));
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+                if names.is_empty() {
+                    record.clear_info_string(key)?;
+                } else {
+                    let values = names.iter().map(|name| name.as_bytes()).collect::<Vec<_>>();
+                    record.push_info_string(key, &values)?;
+                }
+            }
+            AnnotateMode::Score => {
+                scores.clear();
+                scores.extend(
+                    hits.iter()
+                        .filter_map(|idx| set.records()[*idx].score().0)
+                        .map(|score| score as f32),
+                );
+                if scores.is_empty() {
+                    record.clear_info_float(key)?;
+                } else {
+                    record.push_info_float(key, &scores)?;
+                }
+            }
+            AnnotateMode::Flag => {
+                if hits.is_empty() {
+                    record.clear_info_flag(key)?;
+                } else {
+                    record.push_info_flag(key)?;
+                }
+            }
+        }
+        writer.write(&record)?;
+    }
+    Ok(())
+}
+
+/// Writes every BCF record back out and stores the names, scores, or
+/// presence of the overlapping BED intervals in an INFO key.
+///
+/// Records overlapping multiple intervals with the same name only
+/// report that name once. Intervals without a score are skipped in
+/// the score mode.
+pub fn annotate(args: AnnotateArgs) -> Result<()> {
+    let bed_reader = args.inputs.get_reader_bed()?;
+    let (set, translater) = read_annotation_set(bed_reader, args.params.mode)?;
+    let mut bcf_reader = args.inputs.get_reader_bcf()?;
+    let header = build_header(bcf_reader.header(), &args.params, &args.inputs.bed)?;
+    let mut writer = args.output.get_writer_with_header(&header)?;
+    run_annotate(&mut bcf_reader, set, &translater, args.params, &mut writer)
+}
@@ -0,0 +1,101 @@
+use super::utils::{
+    format_info, parse_alt_alleles, parse_chr_name, parse_variant_name, parse_variant_spans,
+    resolve_sample,
+};
+use crate::{
+    cli::bcf::{ConvertArgs, ConvertParams},
+    io::build_writer,
+};
+use anyhow::{bail, Result};
+use rust_htslib::bcf::{header::TagType, Read, Reader as VcfReader};
+use std::{io::Write, str::from_utf8};
+
+/// Resolves the types of the requested INFO keys from the header
+fn resolve_info_types(reader: &VcfReader, keys: &[String]) -> Result<Vec<TagType>> {
+    keys.iter()
+        .map(|key| match reader.header().info_type(key.as_bytes()) {
+            Ok((tag_type, _)) => Ok(tag_type),
+            Err(_) => bail!("INFO key {} is not defined in the header", key),
+        })
+        .collect()
+}
+
+/// Resolves the indices of the samples whose genotypes are reported
+fn resolve_samples(reader: &VcfReader, params: &ConvertParams) -> Result<Vec<usize>> {
+    let header = reader.header();
+    if params.genotypes {
+        return Ok((0..header.sample_count() as usize).collect());
+    }
+    params
+        .samples
+        .iter()
+        .map(|sample| resolve_sample(header, sample))
+        .collect()
+}
+
+fn run_convert<W: Write>(vcf: &mut VcfReader, params: ConvertParams, writer: W) -> Result<()> {
+    let header = vcf.header().clone();
+    let info_types = resolve_info_types(vcf, &params.info)?;
+    let samples = resolve_samples(vcf, &params)?;
+    let mut wtr = build_writer(writer);
+
+    // Reusable buffer of the optional columns
+    let mut extra_columns = Vec::with_capacity(info_types.len() + samples.len());
+
+    // Initialize an empty VCF record to avoid repeated allocations
+    let mut record = vcf.empty_record();
+
+    while let Some(record_result) = vcf.read(&mut record) {
+        record_result?;
+        let chr_name = parse_chr_name(&record, &header)?;
+        let name = parse_variant_name(&record, chr_name)?;
+        let alleles = record.alleles();
+        let ref_allele = from_utf8(alleles.first().copied().unwrap_or(b"."))?;
+        let alt_alleles = parse_alt_alleles(&record)?;
+
+        extra_columns.clear();
+        for (key, tag_type) in params.info.iter().zip(info_types.iter()) {
+            extra_columns.push(format_info(
+                &record,
+                key.as_bytes(),
+                *tag_type,
+                &params.missing,
+            )?);
+        }
+        if !samples.is_empty() {
+            match record.genotypes() {
+                Ok(genotypes) => {
+                    for idx in samples.iter() {
+                        extra_columns.push(genotypes.get(*idx).to_string());
+                    }
+                }
+                Err(_) => {
+                    extra_columns.extend(samples.iter().map(|_| params.missing.clone()));
+                }
+            }
+        }
+
+        // Breakends are reported once for each of their spans
+        for (span_chr, start, end) in parse_variant_spans(&record, &header, &params.span_policy)? {
+            let tuple = (
+                from_utf8(span_chr)?,
+                start,
+                end,
+                &name,
+                ref_allele,
+                &alt_alleles,
+            );
+            wtr.serialize((tuple, &extra_columns))?;
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Writes every BCF record as a BED interval followed by its alleles
+/// and any requested INFO and genotype columns.
+pub fn convert(args: ConvertArgs) -> Result<()> {
+    let mut reader = args.input.get_reader()?;
+    let writer = args.output.get_writer()?;
+    run_convert(&mut reader, args.params, writer)
+}
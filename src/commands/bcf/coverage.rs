@@ -0,0 +1,193 @@
+use super::utils::{
+    classify_variant, has_non_ref_genotype, parse_variant_spans, resolve_sample, VariantType,
+};
+use crate::{
+    cli::bcf::{BcfCoverageArgs, BcfCoverageParams},
+    dispatch_single_with_htslib,
+    io::{build_writer, write_depth_iter_with, WriteNamedIter, WriteNamedIterImpl},
+    types::{IntervalDepth, NumericBed3, Rename, Renamer, SplitTranslater},
+};
+use anyhow::Result;
+use bedrs::{traits::IntervalBounds, IntervalContainer};
+use rust_htslib::bcf::{Read as VcfRead, Reader as VcfReader};
+use serde::Serialize;
+
+/// Counts of the records overlapping a single interval split by class
+#[derive(Default)]
+struct VariantCounts {
+    snv: usize,
+    indel: usize,
+    sv: usize,
+    carrier: usize,
+    non_carrier: usize,
+}
+
+/// The optional columns reported after the overlap count
+#[derive(Serialize)]
+struct CoverageColumns {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snv: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    indel: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sv: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    carrier: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    non_carrier: Option<usize>,
+}
+impl CoverageColumns {
+    fn new(counts: &VariantCounts, params: &BcfCoverageParams) -> Self {
+        let (snv, indel, sv) = if params.by_type {
+            (Some(counts.snv), Some(counts.indel), Some(counts.sv))
+        } else {
+            (None, None, None)
+        };
+        let (carrier, non_carrier) = if params.sample.is_some() {
+            (Some(counts.carrier), Some(counts.non_carrier))
+        } else {
+            (None, None)
+        };
+        Self {
+            snv,
+            indel,
+            sv,
+            carrier,
+            non_carrier,
+        }
+    }
+}
+
+fn has_extra_columns(params: &BcfCoverageParams) -> bool {
+    params.by_type || params.sample.is_some()
+}
+
+fn run_coverage<'a, I, N, W>(
+    vcf: &mut VcfReader,
+    mut set: IntervalContainer<I, usize, usize>,
+    translater: Option<&'a SplitTranslater>,
+    params: BcfCoverageParams,
+    writer: &mut W,
+) -> Result<()>
+where
+    I: IntervalBounds<usize, usize> + Copy + Serialize,
+    N: IntervalBounds<&'a str, usize> + Serialize,
+    W: std::io::Write,
+    WriteNamedIterImpl: WriteNamedIter<I>,
+    Renamer: Rename<'a, I, N>,
+{
+    // Get the header
+    let header = vcf.header().clone();
+
+    // Sort the BED set intervals
+    set.sort();
+
+    // Export the translater (should always be Some)
+    let translater = translater.unwrap();
+
+    // Initialize the overlap query method
+    let query_method = params.overlap_predicates.into();
+
+    // Resolve the sample whose genotypes split the counts
+    let sample_idx = match params.sample.as_ref() {
+        Some(sample) => Some(resolve_sample(&header, sample)?),
+        None => None,
+    };
+
+    let mut coverage = vec![0; set.len()];
+
+    // Only track the per-interval counts if they will be reported
+    let extra_columns = has_extra_columns(&params);
+    let mut counts: Vec<VariantCounts> = if extra_columns {
+        (0..set.len()).map(|_| VariantCounts::default()).collect()
+    } else {
+        Vec::new()
+    };
+
+    // Reusable buffer of the intervals overlapped by a record
+    let mut hits: Vec<usize> = Vec::new();
+
+    // Initialize an empty VCF record to avoid repeated allocations
+    let mut record = vcf.empty_record();
+
+    while let Some(record_result) = vcf.read(&mut record) {
+        record_result?;
+
+        // Intervals overlapping multiple spans of a breakend are only counted once
+        hits.clear();
+        for (chr_bytes, start, end) in parse_variant_spans(&record, &header, &params.span_policy)? {
+            let chr_name = std::str::from_utf8(chr_bytes)?;
+            if let Some(chr_idx) = translater.get_chr_idx(chr_name) {
+                let query = NumericBed3::new(chr_idx, start, end);
+                for (idx, _) in set.query_iter_enumerate(&query, query_method)? {
+                    if !hits.contains(&idx) {
+                        hits.push(idx);
+                    }
+                }
+            }
+        }
+        if hits.is_empty() {
+            continue;
+        }
+
+        hits.iter().for_each(|idx| coverage[*idx] += 1);
+        if extra_columns {
+            let variant_type = classify_variant(&record);
+            let is_carrier = match sample_idx {
+                Some(idx) => has_non_ref_genotype(&record, idx)?,
+                None => false,
+            };
+            for idx in hits.iter() {
+                let iv_counts = &mut counts[*idx];
+                match variant_type {
+                    VariantType::Snv => iv_counts.snv += 1,
+                    VariantType::Indel => iv_counts.indel += 1,
+                    VariantType::Sv => iv_counts.sv += 1,
+                }
+                if is_carrier {
+                    iv_counts.carrier += 1;
+                } else {
+                    iv_counts.non_carrier += 1;
+                }
+            }
+        }
+    }
+
+    if !extra_columns {
+        // Define an iterator over the depth and BED intervals
+        let depth_iter = set
+            .iter()
+            .zip(coverage.iter())
+            .map(|(iv, depth)| IntervalDepth::new(*iv, *depth, Some(translater)));
+
+        // Write the depth iterator to the writer
+        return write_depth_iter_with(depth_iter, writer, Some(translater));
+    }
+
+    // Write the intervals with their count and the requested splits
+    let mut wtr = build_writer(writer);
+    for ((iv, depth), iv_counts) in set.iter().zip(coverage.iter()).zip(counts.iter()) {
+        let named: N = Renamer::rename_with(iv, translater);
+        let columns = CoverageColumns::new(iv_counts, &params);
+        wtr.serialize((named, depth, columns))?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Counts the BCF records overlapping each BED interval.
+///
+/// Like the BAM coverage command the BED intervals are kept in
+/// memory and the BCF file is streamed.
+pub fn coverage(args: BcfCoverageArgs) -> Result<()> {
+    let bed_reader = args.inputs.get_reader_bed()?;
+    let mut bcf_reader = args.inputs.get_reader_bcf()?;
+    let mut writer = args.output.get_writer()?;
+    dispatch_single_with_htslib!(
+        &mut bcf_reader,
+        bed_reader,
+        &mut writer,
+        args.params,
+        run_coverage
+    )
+}
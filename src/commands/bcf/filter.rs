@@ -1,80 +1,59 @@
 use crate::{
-    cli::bcf::{FilterArgs, FilterParams},
+    cli::bcf::{FilterArgs, FilterParams, SamplePredicates, SpanPolicy},
     dispatch_single_with_htslib,
-    io::{WriteNamedIter, WriteNamedIterImpl},
+    io::{RegionBcfReader, WriteNamedIter, WriteNamedIterImpl},
     types::{NumericBed3, SplitTranslater},
 };
 
-use super::utils::{parse_chr_name, parse_endpoints};
+use super::utils::{
+    build_fetch_regions, count_non_ref_genotypes, parse_variant_spans, resolve_sample,
+    spans_outside_locus,
+};
 use anyhow::Result;
 use bedrs::{traits::IntervalBounds, types::Query, IntervalContainer};
-use rust_htslib::bcf::{
-    header::HeaderView, Read as VcfRead, Reader as VcfReader, Record, Writer as VcfWriter,
-};
+use rust_htslib::bcf::{header::HeaderView, Header, Record, Writer as VcfWriter};
 use serde::Serialize;
 
-fn temp_bed3(
-    record: &Record,
-    header: &HeaderView,
-    translater: &SplitTranslater,
-) -> Result<Option<NumericBed3>> {
-    let chr_bytes = parse_chr_name(record, header)?;
-    let chr_name = std::str::from_utf8(chr_bytes)?;
-    let chr_idx = if let Some(idx) = translater.get_chr_idx(chr_name) {
-        idx
-    } else {
-        return Ok(None);
-    };
-    let (start, end) = parse_endpoints(record)?;
-    Ok(Some(NumericBed3::new(chr_idx, start, end)))
-}
-
-fn run_inverted_overlap<I>(
+/// Builds an interval for every span of the record whose chromosome is in the BED set
+fn temp_bed3s(
     record: &Record,
     header: &HeaderView,
-    set: &IntervalContainer<I, usize, usize>,
     translater: &SplitTranslater,
-    query_method: Query<usize>,
-    wtr: &mut VcfWriter,
-) -> Result<()>
-where
-    I: IntervalBounds<usize, usize> + Copy + Serialize,
-    WriteNamedIterImpl: WriteNamedIter<I>,
-{
-    if let Some(bed) = temp_bed3(record, header, translater)? {
-        let no_overlaps = set.query_iter(&bed, query_method)?.next().is_none();
-        if no_overlaps {
-            wtr.write(record)?;
+    policy: &SpanPolicy,
+) -> Result<Vec<NumericBed3>> {
+    let mut intervals = Vec::new();
+    for (chr_bytes, start, end) in parse_variant_spans(record, header, policy)? {
+        let chr_name = std::str::from_utf8(chr_bytes)?;
+        if let Some(chr_idx) = translater.get_chr_idx(chr_name) {
+            intervals.push(NumericBed3::new(chr_idx, start, end));
         }
-    } else {
-        wtr.write(record)?;
     }
-    Ok(())
+    Ok(intervals)
 }
 
-fn run_overlap<I>(
+/// Returns true if any span of the record overlaps the BED set
+fn has_overlap<I>(
     record: &Record,
     header: &HeaderView,
     set: &IntervalContainer<I, usize, usize>,
     translater: &SplitTranslater,
     query_method: Query<usize>,
-    wtr: &mut VcfWriter,
-) -> Result<()>
+    policy: &SpanPolicy,
+) -> Result<bool>
 where
     I: IntervalBounds<usize, usize> + Copy + Serialize,
     WriteNamedIterImpl: WriteNamedIter<I>,
 {
-    if let Some(bed) = temp_bed3(record, header, translater)? {
-        let any_overlaps = set.query_iter(&bed, query_method)?.next().is_some();
-        if any_overlaps {
-            wtr.write(record)?;
+    for bed in temp_bed3s(record, header, translater, policy)? {
+        if set.query_iter(&bed, query_method)?.next().is_some() {
+            return Ok(true);
         }
     }
-    Ok(())
+    Ok(false)
 }
 
 fn run_filter<I>(
-    vcf: &mut VcfReader,
+    vcf: &mut RegionBcfReader,
     mut set: IntervalContainer<I, usize, usize>,
     translater: Option<&SplitTranslater>,
     params: FilterParams,
@@ -96,27 +75,84 @@ where
     // Initialize the overlap query method
     let query_method = params.overlap_predicates.into();
 
-    // Initialize an empty VCF record to avoid repeated allocations
-    let mut record = vcf.empty_record();
+    // Records outside of the BED regions are required when inverting so the full file
+    // is visited, as are records whose spans can reach into a region from outside of
+    // it. Otherwise only the records within the BED regions are fetched if the BCF is
+    // indexed.
+    let invert = params.output_predicates.invert;
+    let stream = invert || params.no_index || spans_outside_locus(&header, &params.span_policy);
+    let regions = if stream {
+        None
+    } else {
+        Some(build_fetch_regions(&set, translater, &header)?)
+    };
+
+    // Resolve the samples whose genotypes are checked for carriers
+    let samples = resolve_samples(&header, &params.sample_predicates)?;
+    let subset = !params.sample_predicates.samples.is_empty();
 
-    if params.output_predicates.invert {
-        while let Some(record_result) = vcf.read(&mut record) {
-            record_result?;
-            run_inverted_overlap(&record, &header, &set, translater, query_method, writer)?;
+    // Records are written if any of their spans overlap (or none do when inverted)
+    vcf.for_each_record(regions.as_deref(), |record| {
+        let overlaps = has_overlap(
+            record,
+            &header,
+            &set,
+            translater,
+            query_method,
+            &params.span_policy,
+        )?;
+        if overlaps == invert {
+            return Ok(());
         }
-    } else {
-        while let Some(record_result) = vcf.read(&mut record) {
-            record_result?;
-            run_overlap(&record, &header, &set, translater, query_method, writer)?;
+        if let Some(min_carriers) = params.sample_predicates.min_carriers {
+            if count_non_ref_genotypes(record, &samples)? < min_carriers {
+                return Ok(());
+            }
+        }
+        if subset {
+            let mut record = record.clone();
+            writer.translate(&mut record);
+            writer.subset(&mut record);
+            writer.write(&record)?;
+        } else {
+            writer.write(record)?;
         }
+        Ok(())
+    })
+}
+
+/// Resolves the indices of the selected samples (or all samples if none are selected)
+fn resolve_samples(header: &HeaderView, predicates: &SamplePredicates) -> Result<Vec<usize>> {
+    if predicates.samples.is_empty() {
+        return Ok((0..header.sample_count() as usize).collect());
+    }
+    predicates
+        .samples
+        .iter()
+        .map(|sample| resolve_sample(header, sample))
+        .collect()
+}
+
+/// Builds the output header, subset to the selected samples if any
+fn build_header(header: &HeaderView, predicates: &SamplePredicates) -> Result<Header> {
+    if predicates.samples.is_empty() {
+        return Ok(Header::from_template(header));
     }
-    Ok(())
+    // Validate the sample names before subsetting
+    resolve_samples(header, predicates)?;
+    let samples = predicates
+        .samples
+        .iter()
+        .map(|sample| sample.as_bytes())
+        .collect::<Vec<_>>();
+    Ok(Header::from_template_subset(header, &samples)?)
 }
 
 pub fn filter(args: FilterArgs) -> Result<()> {
     let bed_reader = args.inputs.get_reader_bed()?;
-    let mut bcf_reader = args.inputs.get_reader_bcf()?;
-    let mut writer = args.output.get_writer(bcf_reader.header())?;
+    let mut bcf_reader = args.inputs.get_region_reader_bcf()?;
+    let header = build_header(bcf_reader.header(), &args.params.sample_predicates)?;
+    let mut writer = args.output.get_writer_with_header(&header)?;
     dispatch_single_with_htslib!(
         &mut bcf_reader,
         bed_reader,
@@ -0,0 +1,213 @@
+use super::utils::{parse_chr_name, parse_variant_spans};
+use crate::{
+    cli::bcf::{BcfIntersectArgs, IntersectReport, MatchMode, SpanPolicy},
+    types::{NumericBed3, NumericBed4},
+};
+use anyhow::Result;
+use bedrs::{types::Query, IntervalContainer};
+use hashbrown::HashMap;
+use rust_htslib::bcf::{
+    header::HeaderView, Read, Reader as VcfReader, Record, Writer as VcfWriter,
+};
+
+/// Key of a record by its chromosome, position, REF, and a single ALT allele
+///
+/// The alleles are left empty when matching by position.
+type VariantKey = (Vec<u8>, i64, Vec<u8>, Vec<u8>);
+
+/// Builds the keys of a record (one for each ALT allele when matching by allele)
+fn variant_keys(record: &Record, header: &HeaderView, mode: MatchMode) -> Result<Vec<VariantKey>> {
+    let chr_name = parse_chr_name(record, header)?.to_vec();
+    let pos = record.pos();
+    if mode == MatchMode::Position {
+        return Ok(vec![(chr_name, pos, Vec::new(), Vec::new())]);
+    }
+    let alleles = record.alleles();
+    let ref_allele = alleles.first().copied().unwrap_or(b".");
+    let keys = alleles
+        .iter()
+        .skip(1)
+        .map(|alt| (chr_name.clone(), pos, ref_allele.to_vec(), alt.to_vec()))
+        .collect();
+    Ok(keys)
+}
+
+/// The records of b indexed for the match mode
+struct VariantIndex {
+    /// Indices of the records of b by their keys (allele and position modes)
+    keys: HashMap<VariantKey, Vec<usize>>,
+
+    /// Spans of the records of b named by their record index (overlap mode)
+    spans: IntervalContainer<NumericBed4, usize, usize>,
+
+    /// Numeric indices of the chromosome names of the spans
+    chr_indices: HashMap<Vec<u8>, usize>,
+
+    /// Records of b (only kept when they are reported)
+    records: Vec<Record>,
+
+    /// Whether each record of b matched a record of a
+    matched: Vec<bool>,
+}
+impl VariantIndex {
+    fn from_reader(
+        reader: &mut VcfReader,
+        mode: MatchMode,
+        span_policy: &SpanPolicy,
+        keep_records: bool,
+    ) -> Result<Self> {
+        let header = reader.header().clone();
+        let mut keys: HashMap<VariantKey, Vec<usize>> = HashMap::new();
+        let mut spans = Vec::new();
+        let mut chr_indices = HashMap::new();
+        let mut records = Vec::new();
+        let mut num_records = 0;
+
+        let mut record = reader.empty_record();
+        while let Some(record_result) = reader.read(&mut record) {
+            record_result?;
+            let idx = num_records;
+            num_records += 1;
+            match mode {
+                MatchMode::Overlap => {
+                    for (chr_bytes, start, end) in
+                        parse_variant_spans(&record, &header, span_policy)?
+                    {
+                        let num_chr = chr_indices.len();
+                        let chr_idx = *chr_indices.entry(chr_bytes.to_vec()).or_insert(num_chr);
+                        spans.push(NumericBed4::new(chr_idx, start, end, idx));
+                    }
+                }
+                _ => {
+                    for key in variant_keys(&record, &header, mode)? {
+                        keys.entry(key).or_default().push(idx);
+                    }
+                }
+            }
+            if keep_records {
+                records.push(record.clone());
+            }
+        }
+
+        let mut spans = IntervalContainer::from_unsorted(spans);
+        spans.sort();
+        Ok(Self {
+            keys,
+            spans,
+            chr_indices,
+            records,
+            matched: vec![false; num_records],
+        })
+    }
+
+    /// Fills the buffer with the indices of the records of b matching the record
+    fn find_matches(
+        &self,
+        record: &Record,
+        header: &HeaderView,
+        mode: MatchMode,
+        span_policy: &SpanPolicy,
+        query_method: Query<usize>,
+        hits: &mut Vec<usize>,
+    ) -> Result<()> {
+        hits.clear();
+        match mode {
+            MatchMode::Overlap => {
+                for (chr_bytes, start, end) in parse_variant_spans(record, header, span_policy)? {
+                    if let Some(chr_idx) = self.chr_indices.get(chr_bytes) {
+                        let query = NumericBed3::new(*chr_idx, start, end);
+                        for iv in self.spans.query_iter(&query, query_method)? {
+                            if !hits.contains(iv.name()) {
+                                hits.push(*iv.name());
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {
+                for key in variant_keys(record, header, mode)? {
+                    if let Some(indices) = self.keys.get(&key) {
+                        for idx in indices {
+                            if !hits.contains(idx) {
+                                hits.push(*idx);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn run_intersect(
+    vcf: &mut VcfReader,
+    mut index: VariantIndex,
+    args: &BcfIntersectArgs,
+    writer: &mut VcfWriter,
+) -> Result<()> {
+    // Get the header
+    let header = vcf.header().clone();
+
+    // Initialize the overlap query method
+    let query_method = args.params.overlap_predicates.into();
+
+    let mode = args.params.mode;
+    let report = args.params.report;
+
+    // Reusable buffer of the matching record indices of b
+    let mut hits: Vec<usize> = Vec::new();
+
+    // Initialize an empty VCF record to avoid repeated allocations
+    let mut record = vcf.empty_record();
+
+    while let Some(record_result) = vcf.read(&mut record) {
+        record_result?;
+        index.find_matches(
+            &record,
+            &header,
+            mode,
+            &args.params.span_policy,
+            query_method,
+            &mut hits,
+        )?;
+        match report {
+            IntersectReport::Shared if !hits.is_empty() => writer.write(&record)?,
+            IntersectReport::AOnly if hits.is_empty() => writer.write(&record)?,
+            IntersectReport::BOnly => hits.iter().for_each(|idx| index.matched[*idx] = true),
+            _ => {}
+        }
+    }
+
+    // The unmatched records of b can only be known once a is exhausted
+    if report == IntersectReport::BOnly {
+        for (record, matched) in index.records.iter().zip(index.matched.iter()) {
+            if !matched {
+                writer.write(record)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Matches the records of two BCF files and writes either the shared
+/// records of a, the records unique to a, or the records unique to b.
+///
+/// The output header is taken from whichever file the records are written from.
+pub fn intersect(args: BcfIntersectArgs) -> Result<()> {
+    let mut a_reader = args.inputs.get_reader_a()?;
+    let mut b_reader = args.inputs.get_reader_b()?;
+    let report_b = args.params.report == IntersectReport::BOnly;
+    let index = VariantIndex::from_reader(
+        &mut b_reader,
+        args.params.mode,
+        &args.params.span_policy,
+        report_b,
+    )?;
+    let mut writer = if report_b {
+        args.output.get_writer(b_reader.header())?
+    } else {
+        args.output.get_writer(a_reader.header())?
+    };
+    run_intersect(&mut a_reader, index, &args, &mut writer)
+}
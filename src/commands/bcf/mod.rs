@@ -1,4 +1,12 @@
+mod annotate;
+mod convert;
+mod coverage;
 mod filter;
+mod intersect;
 pub mod utils;
 
+pub use annotate::annotate;
+pub use convert::convert;
+pub use coverage::coverage;
 pub use filter::filter;
+pub use intersect::intersect;
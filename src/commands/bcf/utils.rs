@@ -1,5 +1,14 @@
+use crate::{
+    cli::bcf::SpanPolicy,
+    types::{SplitTranslater, Translate},
+};
 use anyhow::{bail, Result};
-use rust_htslib::bcf::{header::HeaderView, Record};
+use bedrs::{traits::IntervalBounds, IntervalContainer};
+use rust_htslib::bcf::{
+    header::{HeaderView, TagType},
+    record::Numeric,
+    Record,
+};
 
 pub fn parse_chr_name<'a>(record: &Record, header: &'a HeaderView) -> Result<&'a [u8]> {
     if let Some(rid) = record.rid() {
@@ -10,8 +19,309 @@ pub fn parse_chr_name<'a>(record: &Record, header: &'a HeaderView) -> Result<&'a
     }
 }
 
-pub fn parse_endpoints(record: &Record) -> Result<(usize, usize)> {
+/// Builds the sorted and merged `(rid, start, end)` regions of a BED set for indexed queries
+///
+/// Assumes the set is already sorted. Chromosomes missing from the BCF header are skipped.
+pub fn build_fetch_regions<I>(
+    set: &IntervalContainer<I, usize, usize>,
+    translater: &SplitTranslater,
+    header: &HeaderView,
+) -> Result<Vec<(u32, usize, usize)>>
+where
+    I: IntervalBounds<usize, usize>,
+{
+    let merged = set.merge()?;
+    let mut regions = merged
+        .records()
+        .iter()
+        .filter_map(|iv| {
+            let chr_name = translater.get_chr_name(*iv.chr())?;
+            let rid = header.name2rid(chr_name.as_bytes()).ok()?;
+            Some((rid, iv.start(), iv.end()))
+        })
+        .collect::<Vec<_>>();
+    regions.sort_unstable();
+    Ok(regions)
+}
+
+/// Returns true if the span policy can place a span outside of the indexed locus of a record
+///
+/// Indexes only cover the REF allele (or INFO/END) of a record, so breakend mates,
+/// SVLEN-only spans, and confidence interval padding can reach into a region from a
+/// record that an indexed fetch never visits. Files whose header does not declare the
+/// INFO keys behind these spans keep every span within the indexed locus.
+pub fn spans_outside_locus(header: &HeaderView, policy: &SpanPolicy) -> bool {
+    let has_info = |key: &[u8]| header.info_type(key).is_ok();
+    let has_mates = !policy.ignore_mates && (has_info(b"SVTYPE") || has_info(b"MATEID"));
+    let has_padding = policy.ci_padding && (has_info(b"CIPOS") || has_info(b"CIEND"));
+    has_mates || has_padding || has_info(b"SVLEN")
+}
+
+/// Parses the first value of an integer INFO key (None if missing or undefined)
+pub fn parse_info_integer(record: &Record, key: &[u8]) -> Option<i64> {
+    match record.info(key).integer() {
+        Ok(Some(values)) => values
+            .first()
+            .filter(|value| !value.is_missing())
+            .map(|value| *value as i64),
+        _ => None,
+    }
+}
+
+/// Parses a confidence interval INFO key (e.g. CIPOS) as its lower and upper bounds
+fn parse_info_interval(record: &Record, key: &[u8]) -> Option<(i64, i64)> {
+    match record.info(key).integer() {
+        Ok(Some(values)) => {
+            let lower = values.first().filter(|value| !value.is_missing())?;
+            let upper = values
+                .get(1)
+                .filter(|value| !value.is_missing())
+                .unwrap_or(lower);
+            Some((*lower as i64, *upper as i64))
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if any ALT allele is symbolic (e.g. `<DEL>`)
+pub fn is_symbolic(record: &Record) -> bool {
+    record
+        .alleles()
+        .iter()
+        .skip(1)
+        .any(|allele| allele.starts_with(b"<"))
+}
+
+/// Parses the 0-based half-open span of a variant on its own chromosome
+///
+/// The span covers the REF allele. Symbolic structural variants only
+/// carry their padding base in REF, so their end is taken from INFO/END
+/// or from the length of INFO/SVLEN following the padding base.
+/// Symbolic insertions do not consume any reference bases and keep the
+/// REF span.
+pub fn parse_variant_span(record: &Record, policy: &SpanPolicy) -> (usize, usize) {
     let start = record.pos() as usize;
-    let end = record.end() as usize;
-    Ok((start, end))
+    let alleles = record.alleles();
+    let ref_len = alleles.first().map_or(1, |allele| allele.len().max(1));
+    let mut end = start + ref_len;
+    let is_insertion = alleles
+        .iter()
+        .skip(1)
+        .all(|allele| allele.starts_with(b"<INS"));
+    if is_symbolic(record) && !is_insertion {
+        if let Some(info_end) = parse_info_integer(record, b"END") {
+            end = end.max(info_end as usize);
+        } else if let Some(svlen) = parse_info_integer(record, b"SVLEN") {
+            end = end.max(start + 1 + svlen.unsigned_abs() as usize);
+        }
+    }
+    if policy.ci_padding {
+        pad_span(record, start, end)
+    } else {
+        (start, end)
+    }
+}
+
+/// Extends a span by the lower bound of CIPOS and the upper bound of CIEND
+/// (or CIPOS if the record has no CIEND)
+fn pad_span(record: &Record, start: usize, end: usize) -> (usize, usize) {
+    let cipos = parse_info_interval(record, b"CIPOS");
+    let ciend = parse_info_interval(record, b"CIEND").or(cipos);
+    let start = match cipos {
+        Some((lower, _)) => (start as i64 + lower.min(0)).max(0) as usize,
+        None => start,
+    };
+    let end = match ciend {
+        Some((_, upper)) => end + upper.max(0) as usize,
+        None => end,
+    };
+    (start, end)
+}
+
+/// Parses the mate chromosome and 0-based position of a breakend ALT allele
+///
+/// Mates are encoded as `t[p[`, `t]p]`, `]p]t`, or `[p[t` where `p` is
+/// `chr:pos`. Single breakends (e.g. `t.`) have no mate.
+fn parse_breakend_mate(allele: &[u8]) -> Option<(&[u8], usize)> {
+    let open = allele.iter().position(|c| *c == b'[' || *c == b']')?;
+    let bracket = allele[open];
+    let close = open + 1 + allele[open + 1..].iter().position(|c| *c == bracket)?;
+    let mate = &allele[open + 1..close];
+    let colon = mate.iter().rposition(|c| *c == b':')?;
+    let pos = std::str::from_utf8(&mate[colon + 1..])
+        .ok()?
+        .parse::<usize>()
+        .ok()?;
+    if pos == 0 {
+        return None;
+    }
+    Some((&mate[..colon], pos - 1))
+}
+
+/// Parses every interval spanned by a variant with its chromosome name
+///
+/// The first interval is always the span of the record on its own chromosome.
+/// Breakends (BND) add a point interval for each mate encoded in their ALT
+/// alleles unless mates are ignored.
+pub fn parse_variant_spans<'a>(
+    record: &'a Record,
+    header: &'a HeaderView,
+    policy: &SpanPolicy,
+) -> Result<Vec<(&'a [u8], usize, usize)>> {
+    let chr_name = parse_chr_name(record, header)?;
+    let (start, end) = parse_variant_span(record, policy);
+    let mut spans = vec![(chr_name, start, end)];
+    if !policy.ignore_mates {
+        for allele in record.alleles().into_iter().skip(1) {
+            if let Some((mate_chr, mate_pos)) = parse_breakend_mate(allele) {
+                spans.push((mate_chr, mate_pos, mate_pos + 1));
+            }
+        }
+    }
+    Ok(spans)
+}
+
+/// The broad class of a variant record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariantType {
+    Snv,
+    Indel,
+    Sv,
+}
+
+/// Returns true if the allele is a breakend with a mate or a single breakend
+fn is_breakend(allele: &[u8]) -> bool {
+    allele.contains(&b'[')
+        || allele.contains(&b']')
+        || (allele.len() > 1 && (allele.starts_with(b".") || allele.ends_with(b".")))
+}
+
+/// Classifies a record as a structural variant, an indel, or an SNV
+///
+/// Records with symbolic or breakend ALT alleles or an INFO/SVTYPE are
+/// structural variants. Records with an ALT allele of a different length
+/// than REF are indels, and all other records (including MNVs) are SNVs.
+pub fn classify_variant(record: &Record) -> VariantType {
+    let alleles = record.alleles();
+    let has_svtype = matches!(record.info(b"SVTYPE").string(), Ok(Some(_)));
+    if has_svtype || is_symbolic(record) || alleles.iter().skip(1).any(|allele| is_breakend(allele))
+    {
+        return VariantType::Sv;
+    }
+    let ref_len = alleles.first().map_or(0, |allele| allele.len());
+    if alleles.iter().skip(1).any(|allele| allele.len() != ref_len) {
+        VariantType::Indel
+    } else {
+        VariantType::Snv
+    }
+}
+
+/// Resolves the index of a sample in the header
+pub fn resolve_sample(header: &HeaderView, sample: &str) -> Result<usize> {
+    match header.sample_id(sample.as_bytes()) {
+        Some(idx) => Ok(idx),
+        None => bail!("Sample {} is not present in the header", sample),
+    }
+}
+
+/// Counts the samples carrying at least one non-reference allele
+pub fn count_non_ref_genotypes(record: &Record, samples: &[usize]) -> Result<usize> {
+    let genotypes = record.genotypes()?;
+    Ok(samples
+        .iter()
+        .filter(|idx| {
+            genotypes
+                .get(**idx)
+                .iter()
+                .any(|allele| matches!(allele.index(), Some(idx) if idx > 0))
+        })
+        .count())
+}
+
+/// Returns true if the sample carries at least one non-reference allele
+pub fn has_non_ref_genotype(record: &Record, sample_idx: usize) -> Result<bool> {
+    Ok(count_non_ref_genotypes(record, &[sample_idx])? > 0)
+}
+
+/// Builds the name of a variant from its ID or from `CHROM:POS:REF:ALT`
+/// if the record has no ID
+pub fn parse_variant_name(record: &Record, chr_name: &[u8]) -> Result<String> {
+    let id = record.id();
+    if id != b"." {
+        return Ok(String::from_utf8(id)?);
+    }
+    let alleles = record.alleles();
+    let ref_allele = alleles.first().copied().unwrap_or(b".");
+    Ok(format!(
+        "{}:{}:{}:{}",
+        std::str::from_utf8(chr_name)?,
+        record.pos() + 1,
+        std::str::from_utf8(ref_allele)?,
+        parse_alt_alleles(record)?,
+    ))
+}
+
+/// Joins the ALT alleles of a record with commas (`.` if there are none)
+pub fn parse_alt_alleles(record: &Record) -> Result<String> {
+    let alleles = record.alleles();
+    if alleles.len() < 2 {
+        return Ok(".".to_string());
+    }
+    let alts = alleles[1..]
+        .iter()
+        .map(|allele| std::str::from_utf8(allele))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(alts.join(","))
+}
+
+/// Formats the values of an INFO key as they would appear in the VCF
+///
+/// Multiple values are comma-separated, missing values within a list
+/// are reported as `.`, and set flags are reported as `1`.
+pub fn format_info(
+    record: &Record,
+    key: &[u8],
+    tag_type: TagType,
+    missing: &str,
+) -> Result<String> {
+    fn join<T: ToString>(values: impl Iterator<Item = Option<T>>) -> String {
+        values
+            .map(|v| v.map_or(".".to_string(), |v| v.to_string()))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+    let value = match tag_type {
+        TagType::Flag => {
+            if record.info(key).flag()? {
+                Some("1".to_string())
+            } else {
+                None
+            }
+        }
+        TagType::Integer => record.info(key).integer()?.map(|values| {
+            join(
+                values
+                    .iter()
+                    .map(|v| if v.is_missing() { None } else { Some(*v) }),
+            )
+        }),
+        TagType::Float => record.info(key).float()?.map(|values| {
+            join(
+                values
+                    .iter()
+                    .map(|v| if v.is_missing() { None } else { Some(*v) }),
+            )
+        }),
+        TagType::String => match record.info(key).string()? {
+            Some(values) => Some(
+                values
+                    .iter()
+                    .map(|v| std::str::from_utf8(v))
+                    .collect::<std::result::Result<Vec<_>, _>>()?
+                    .join(","),
+            ),
+            None => None,
+        },
+    };
+    Ok(value.unwrap_or_else(|| missing.to_string()))
 }
@@ -9,7 +9,7 @@ use bedrs::{traits::IntervalBounds, IntervalContainer};
 use serde::Serialize;
 use std::io::Write;
 
-fn extend_interval<I>(iv: &mut I, left: usize, right: usize, genome: Option<&Genome>)
+pub fn extend_interval<I>(iv: &mut I, left: usize, right: usize, genome: Option<&Genome>)
 where
     I: IntervalBounds<usize, usize>,
 {
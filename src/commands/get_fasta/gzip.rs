@@ -79,7 +79,7 @@ fn dispatch_get_fasta_gzip<R: Read, W: Write>(
                     &mut output,
                 )?;
             }
-            InputFormat::Bed6 => {
+            InputFormat::Bed6 | InputFormat::NarrowPeak | InputFormat::BroadPeak => {
                 let record: NamedBed6 = byterecord.deserialize(None)?;
                 write_fasta_gzip(
                     seq_names,
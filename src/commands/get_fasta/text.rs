@@ -54,7 +54,7 @@ fn dispatch_get_fasta<R: Read, W: Write>(
                 let record: NamedBed4 = byterecord.deserialize(None)?;
                 write_fasta(&record, &fasta, params, &mut shared_buffer, &mut output)?;
             }
-            InputFormat::Bed6 => {
+            InputFormat::Bed6 | InputFormat::NarrowPeak | InputFormat::BroadPeak => {
                 let record: NamedBed6 = byterecord.deserialize(None)?;
                 write_fasta(&record, &fasta, params, &mut shared_buffer, &mut output)?;
             }
@@ -0,0 +1,51 @@
+use crate::{
+    cli::gff::{GffConvertArgs, GffConvertParams},
+    io::{build_writer, BedReader},
+    types::{GffHierarchy, InputFormat},
+};
+use anyhow::{bail, Result};
+use std::io::Write;
+
+/// Reads every feature of a GFF3 file
+fn read_hierarchy(bed_reader: BedReader) -> Result<GffHierarchy> {
+    match bed_reader.input_format() {
+        InputFormat::Gff => GffHierarchy::from_reader(bed_reader.reader()),
+        format => bail!("GFF3 features are required but found {:?}", format),
+    }
+}
+
+fn run_convert<W: Write>(
+    hierarchy: &GffHierarchy,
+    params: GffConvertParams,
+    writer: W,
+) -> Result<()> {
+    let mut wtr = build_writer(writer);
+    for (idx, record) in hierarchy.records().iter().enumerate() {
+        if !params.feature.is_empty() && !params.feature.contains(&record.feature) {
+            continue;
+        }
+        let name = hierarchy
+            .resolve_attribute(idx, &params.name, params.ancestor.as_deref())
+            .unwrap_or(&params.missing);
+        let tuple = (
+            &record.seqid,
+            record.start.saturating_sub(1),
+            record.end,
+            name,
+            &record.score,
+            &record.strand,
+        );
+        wtr.serialize(tuple)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Writes the selected GFF3 features as BED6 intervals named by an
+/// attribute of the feature or of its ancestors.
+pub fn convert(args: GffConvertArgs) -> Result<()> {
+    let bed_reader = args.input.get_reader()?;
+    let hierarchy = read_hierarchy(bed_reader)?;
+    let writer = args.output.get_writer()?;
+    run_convert(&hierarchy, args.params, writer)
+}
@@ -0,0 +1,50 @@
+use crate::{
+    cli::gtf::{GtfFilterArgs, GtfOutputFormat},
+    io::{build_reader, build_writer},
+    types::NamedGtf,
+};
+use anyhow::Result;
+use bedrs::Coordinates;
+use csv::ByteRecord;
+
+/// Writes the GTF features passing the selection either unchanged or
+/// demoted to BED4/BED6 intervals.
+///
+/// Records are streamed and never held in memory.
+pub fn filter(args: GtfFilterArgs) -> Result<()> {
+    let bed_reader = args.input.get_reader()?;
+    let mut reader = build_reader(bed_reader.reader());
+    let mut wtr = build_writer(args.output.get_writer()?);
+    let selection = args.selection;
+    let mut raw_record = ByteRecord::new();
+    while reader.read_byte_record(&mut raw_record)? {
+        let record: NamedGtf = raw_record.deserialize(None)?;
+        if !selection.matches(record.feature(), record.attributes()) {
+            continue;
+        }
+        // GTF coordinates are 1-based and closed while BED is 0-based and half-open
+        let start = record.start().saturating_sub(1);
+        match args.params.output_format {
+            GtfOutputFormat::Gtf => wtr.write_byte_record(&raw_record)?,
+            GtfOutputFormat::Bed4 => {
+                let name = selection.get_name(record.attributes());
+                wtr.serialize((record.chr(), start, record.end(), name))?
+            }
+            GtfOutputFormat::Bed6 => {
+                let name = selection.get_name(record.attributes());
+                let strand = record.strand().unwrap_or_default();
+                let tuple = (
+                    record.chr(),
+                    start,
+                    record.end(),
+                    name,
+                    record.score(),
+                    strand,
+                );
+                wtr.serialize(tuple)?
+            }
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use super::model::{read_transcripts, write_span};
+use crate::{cli::gtf::GtfModelArgs, io::build_writer};
+use anyhow::Result;
+
+/// Writes the gaps between the exons of each transcript
+pub fn introns(args: GtfModelArgs) -> Result<()> {
+    let (transcripts, translater) = read_transcripts(&args.input, &args.model)?;
+    let mut wtr = build_writer(args.output.get_writer()?);
+    for transcript in transcripts.iter() {
+        for intron in transcript.introns() {
+            write_span(&mut wtr, &translater, transcript, intron)?;
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
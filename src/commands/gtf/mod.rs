@@ -0,0 +1,14 @@
+mod filter;
+mod introns;
+mod model;
+mod promoters;
+mod termini;
+mod transcripts;
+mod utrs;
+
+pub use filter::filter;
+pub use introns::introns;
+pub use promoters::promoters;
+pub use termini::{tes, tss};
+pub use transcripts::transcripts;
+pub use utrs::utrs;
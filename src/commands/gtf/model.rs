@@ -0,0 +1,187 @@
+use crate::{
+    cli::{
+        gtf::{TranscriptModelOptions, UtrEnd},
+        SingleInputGtf,
+    },
+    types::{GtfSet, SplitTranslater, Translate},
+    utils::parse_gtf_attribute,
+};
+use anyhow::{bail, Result};
+use bedrs::{Coordinates, Strand};
+use hashbrown::HashMap;
+use std::io::Write;
+
+/// The exons and coding span of a single transcript
+///
+/// Coordinates are 0-based and half-open and the exons are sorted by position.
+pub struct Transcript {
+    pub chr: usize,
+    pub name: String,
+    pub strand: Strand,
+    pub exons: Vec<(usize, usize)>,
+    pub cds: Option<(usize, usize)>,
+}
+impl Transcript {
+    fn new(chr: usize, name: &str, strand: Strand) -> Self {
+        Self {
+            chr,
+            name: name.to_string(),
+            strand,
+            exons: Vec::new(),
+            cds: None,
+        }
+    }
+
+    fn is_reverse(&self) -> bool {
+        self.strand == Strand::Reverse
+    }
+
+    pub fn start(&self) -> usize {
+        self.exons[0].0
+    }
+
+    pub fn end(&self) -> usize {
+        self.exons[self.exons.len() - 1].1
+    }
+
+    /// Returns the coding span (an empty span at the start for non-coding transcripts)
+    pub fn thick(&self) -> (usize, usize) {
+        self.cds.unwrap_or((self.start(), self.start()))
+    }
+
+    /// Returns the gaps between consecutive exons
+    pub fn introns(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.exons
+            .windows(2)
+            .map(|pair| (pair[0].1, pair[1].0))
+            .filter(|(start, end)| start < end)
+    }
+
+    /// Returns the parts of the exons upstream (5') or downstream (3') of the coding span
+    pub fn utrs(&self, utr_end: UtrEnd) -> Vec<(usize, usize)> {
+        let (cds_start, cds_end) = match self.cds {
+            Some(cds) => cds,
+            None => return Vec::new(),
+        };
+        // The 5' UTR of a reverse strand transcript follows the CDS in genome order
+        let before_cds = (utr_end == UtrEnd::Five) != self.is_reverse();
+        self.exons
+            .iter()
+            .map(|(start, end)| {
+                if before_cds {
+                    (*start, (*end).min(cds_start))
+                } else {
+                    ((*start).max(cds_end), *end)
+                }
+            })
+            .filter(|(start, end)| start < end)
+            .collect()
+    }
+
+    /// Returns the position of the first transcribed base
+    pub fn tss(&self) -> usize {
+        if self.is_reverse() {
+            self.end() - 1
+        } else {
+            self.start()
+        }
+    }
+
+    /// Returns the position of the last transcribed base
+    pub fn tes(&self) -> usize {
+        if self.is_reverse() {
+            self.start()
+        } else {
+            self.end() - 1
+        }
+    }
+}
+
+/// Groups the exon, CDS, and stop codon features of a GTF into transcripts by their `transcript_id`
+///
+/// Transcripts are kept in their order of appearance and those without exons are dropped.
+fn build_transcripts(
+    set: &GtfSet,
+    translater: &SplitTranslater,
+    options: &TranscriptModelOptions,
+) -> Result<Vec<Transcript>> {
+    let mut transcript_idx = HashMap::new();
+    let mut transcripts: Vec<Transcript> = Vec::new();
+    for iv in set.records() {
+        // The stop codon is excluded from the CDS features but is part of the coding span
+        let feature = translater.get_meta_name(*iv.feature()).unwrap_or_default();
+        let is_exon = match feature {
+            "exon" => true,
+            "CDS" | "stop_codon" => false,
+            _ => continue,
+        };
+        let attributes = translater
+            .get_meta_name(*iv.attributes())
+            .unwrap_or_default();
+        if !options.selection.matches(feature, attributes) {
+            continue;
+        }
+        let transcript_id = if let Some(id) = parse_gtf_attribute(attributes, "transcript_id") {
+            id
+        } else {
+            continue;
+        };
+        let idx = *transcript_idx
+            .entry(transcript_id.to_string())
+            .or_insert_with(|| {
+                let name = options.selection.get_name(attributes);
+                let strand = iv.strand().unwrap_or_default();
+                transcripts.push(Transcript::new(*iv.chr(), name, strand));
+                transcripts.len() - 1
+            });
+        let transcript = &mut transcripts[idx];
+        if transcript.chr != *iv.chr() {
+            bail!(
+                "Transcript {} has features on multiple chromosomes",
+                transcript_id
+            );
+        }
+        // GTF coordinates are 1-based and closed
+        let (start, end) = (iv.start().saturating_sub(1), iv.end());
+        if is_exon {
+            transcript.exons.push((start, end));
+        } else {
+            transcript.cds = match transcript.cds {
+                Some((cds_start, cds_end)) => Some((cds_start.min(start), cds_end.max(end))),
+                None => Some((start, end)),
+            };
+        }
+    }
+    transcripts.retain(|transcript| !transcript.exons.is_empty());
+    if transcripts.is_empty() {
+        bail!("No exon features with a `transcript_id` attribute were found in the GTF");
+    }
+    transcripts
+        .iter_mut()
+        .for_each(|transcript| transcript.exons.sort_unstable());
+    Ok(transcripts)
+}
+
+/// Reads a GTF and builds its transcript models
+pub fn read_transcripts(
+    input: &SingleInputGtf,
+    options: &TranscriptModelOptions,
+) -> Result<(Vec<Transcript>, SplitTranslater)> {
+    let (set, translater) = input.get_reader()?.gtf_set()?;
+    // The GTF is always read as string-based so the translater is always present
+    let translater = translater.unwrap();
+    let transcripts = build_transcripts(&set, &translater, options)?;
+    Ok((transcripts, translater))
+}
+
+/// Writes a span of a transcript as a BED6 interval named by the transcript
+pub fn write_span<W: Write>(
+    wtr: &mut csv::Writer<W>,
+    translater: &SplitTranslater,
+    transcript: &Transcript,
+    (start, end): (usize, usize),
+) -> Result<()> {
+    let chr = translater.get_chr_name(transcript.chr).unwrap();
+    wtr.serialize((chr, start, end, &transcript.name, 0, transcript.strand))?;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use super::model::{read_transcripts, write_span};
+use crate::{
+    cli::{gtf::GtfPromotersArgs, Growth},
+    commands::extend::extend_interval,
+    io::build_writer,
+    types::TranslateGroup,
+};
+use anyhow::Result;
+use bedrs::{Coordinates, StrandedBed3};
+
+/// Grows the TSS of each transcript into a promoter window
+///
+/// Growth is always applied relative to the transcript strand so that
+/// the left side is upstream of the TSS.
+pub fn promoters(args: GtfPromotersArgs) -> Result<()> {
+    let (transcripts, translater) = read_transcripts(&args.input, &args.model)?;
+    let growth = Growth {
+        stranded: true,
+        ..args.growth
+    };
+    growth.warn_args();
+    let genome = growth.get_genome(Some(translater.get_translater(TranslateGroup::Chr)))?;
+    let mut wtr = build_writer(args.output.get_writer()?);
+    for transcript in transcripts.iter() {
+        let tss = transcript.tss();
+        let mut iv = StrandedBed3::new(transcript.chr, tss, tss + 1, transcript.strand);
+        let (left, right) = growth.get_values(&iv);
+        extend_interval(&mut iv, left, right, genome.as_ref());
+        write_span(&mut wtr, &translater, transcript, (iv.start(), iv.end()))?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use super::model::{read_transcripts, write_span, Transcript};
+use crate::{cli::gtf::GtfModelArgs, io::build_writer};
+use anyhow::Result;
+
+/// Writes a single base of each transcript
+fn write_points(args: GtfModelArgs, position: fn(&Transcript) -> usize) -> Result<()> {
+    let (transcripts, translater) = read_transcripts(&args.input, &args.model)?;
+    let mut wtr = build_writer(args.output.get_writer()?);
+    for transcript in transcripts.iter() {
+        let pos = position(transcript);
+        write_span(&mut wtr, &translater, transcript, (pos, pos + 1))?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Writes the transcription start site of each transcript (strand-aware)
+pub fn tss(args: GtfModelArgs) -> Result<()> {
+    write_points(args, Transcript::tss)
+}
+
+/// Writes the transcription end site of each transcript (strand-aware)
+pub fn tes(args: GtfModelArgs) -> Result<()> {
+    write_points(args, Transcript::tes)
+}
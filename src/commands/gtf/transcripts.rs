@@ -0,0 +1,39 @@
+use super::model::read_transcripts;
+use crate::{cli::gtf::GtfModelArgs, io::build_writer, types::Translate};
+use anyhow::Result;
+
+/// Joins the values of the blocks of a transcript into a BED12 list
+fn join_blocks<I: Iterator<Item = usize>>(values: I) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/// Writes each transcript as a BED12 interval with its exons as blocks
+/// and its coding span as the thick span.
+pub fn transcripts(args: GtfModelArgs) -> Result<()> {
+    let (transcripts, translater) = read_transcripts(&args.input, &args.model)?;
+    let mut wtr = build_writer(args.output.get_writer()?);
+    for transcript in transcripts.iter() {
+        let chr = translater.get_chr_name(transcript.chr).unwrap();
+        let start = transcript.start();
+        let (thick_start, thick_end) = transcript.thick();
+        let block_sizes = join_blocks(transcript.exons.iter().map(|(s, e)| e - s));
+        let block_starts = join_blocks(transcript.exons.iter().map(|(s, _)| s - start));
+        let tuple = (
+            chr,
+            start,
+            transcript.end(),
+            &transcript.name,
+            0,
+            transcript.strand,
+            thick_start,
+            thick_end,
+            0,
+            transcript.exons.len(),
+            block_sizes,
+            block_starts,
+        );
+        wtr.serialize(tuple)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
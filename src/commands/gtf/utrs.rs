@@ -0,0 +1,16 @@
+use super::model::{read_transcripts, write_span};
+use crate::{cli::gtf::GtfUtrsArgs, io::build_writer};
+use anyhow::Result;
+
+/// Writes the 5' or 3' UTRs of each coding transcript
+pub fn utrs(args: GtfUtrsArgs) -> Result<()> {
+    let (transcripts, translater) = read_transcripts(&args.input, &args.model)?;
+    let mut wtr = build_writer(args.output.get_writer()?);
+    for transcript in transcripts.iter() {
+        for utr in transcript.utrs(args.params.end) {
+            write_span(&mut wtr, &translater, transcript, utr)?;
+        }
+    }
+    wtr.flush()?;
+    Ok(())
+}
@@ -73,15 +73,25 @@ fn merge_streamed_by_format<W: Write>(
     writer: W,
     params: MergeParams,
 ) -> Result<()> {
-    if bed_reader.is_named() {
+    let input_format = bed_reader.input_format();
+    // BED3+N and peak payloads are dropped when streaming, so only the chromosome matters
+    let is_named = match input_format {
+        InputFormat::Ambiguous | InputFormat::NarrowPeak | InputFormat::BroadPeak => {
+            bed_reader.has_named_chr()?
+        }
+        _ => bed_reader.is_named(),
+    };
+    if is_named {
         return Err(anyhow::anyhow!(
             "Named input is not supported for streaming"
         ));
     }
-    let input_format = bed_reader.input_format();
     let mut csv_reader = build_reader(bed_reader.reader());
     match input_format {
-        InputFormat::Bed3 | InputFormat::Ambiguous => {
+        InputFormat::Bed3
+        | InputFormat::Ambiguous
+        | InputFormat::NarrowPeak
+        | InputFormat::BroadPeak => {
             let record_iter: Box<dyn Iterator<Item = NumericBed3>> = iter_unnamed(&mut csv_reader);
             merge_streamed(record_iter, writer, params)
         }
@@ -102,7 +112,7 @@ fn merge_streamed_by_format<W: Write>(
             let record_iter: Box<dyn Iterator<Item = NumericBed12>> = iter_unnamed(&mut csv_reader);
             merge_streamed(record_iter, writer, params)
         }
-        InputFormat::Gtf => {
+        InputFormat::Gtf | InputFormat::Gff => {
             let record_iter: Box<dyn Iterator<Item = NumericGtf>> = iter_unnamed(&mut csv_reader);
             merge_streamed(record_iter, writer, params)
         }
@@ -7,9 +7,12 @@ mod coverage;
 mod extend;
 mod flank;
 mod get_fasta;
+pub mod gff;
+pub mod gtf;
 mod intersect;
 mod join;
 mod merge;
+pub mod peak;
 mod random;
 mod sample;
 mod segment;
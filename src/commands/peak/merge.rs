@@ -0,0 +1,71 @@
+use super::utils::read_peaks;
+use crate::{
+    cli::peak::{PeakMergeArgs, PeakStat},
+    io::write_records_iter,
+    types::Peak,
+};
+use anyhow::Result;
+
+/// Collapses a group of overlapping peaks into the strongest peak spanning all of them
+fn merge_group(group: &[Peak], by: PeakStat) -> Peak {
+    let start = group
+        .iter()
+        .map(|peak| peak.start)
+        .min()
+        .unwrap_or_default();
+    let end = group.iter().map(|peak| peak.end).max().unwrap_or_default();
+
+    // The first peak is kept on ties
+    let mut strongest = &group[0];
+    for peak in group.iter().skip(1) {
+        if by.value(peak) > by.value(strongest) {
+            strongest = peak;
+        }
+    }
+
+    // The summit offset is moved to the merged start (peaks without a summit keep -1)
+    let mut merged = strongest.clone();
+    merged.start = start;
+    merged.end = end;
+    merged.peak = strongest.peak.map(|offset| {
+        if offset >= 0 {
+            (strongest.start - start) as i64 + offset
+        } else {
+            offset
+        }
+    });
+    merged
+}
+
+/// Merges overlapping or bookended peaks while keeping the strongest summit
+pub fn merge(args: PeakMergeArgs) -> Result<()> {
+    let bed_reader = args.input.get_reader()?;
+    let (mut peaks, _) = read_peaks(bed_reader)?;
+    let by = args.params.by;
+
+    peaks.sort_by(|a, b| (&a.chr, a.start, a.end).cmp(&(&b.chr, b.start, b.end)));
+
+    let mut merged = Vec::new();
+    let mut group: Vec<Peak> = Vec::new();
+    let mut group_end = 0;
+    for peak in peaks {
+        if let Some(first) = group.first() {
+            if first.chr != peak.chr || peak.start > group_end {
+                merged.push(merge_group(&group, by));
+                group.clear();
+            }
+        }
+        if group.is_empty() {
+            group_end = peak.end;
+        } else {
+            group_end = group_end.max(peak.end);
+        }
+        group.push(peak);
+    }
+    if !group.is_empty() {
+        merged.push(merge_group(&group, by));
+    }
+
+    let writer = args.output.get_writer()?;
+    write_records_iter(merged.into_iter(), writer)
+}
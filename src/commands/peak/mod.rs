@@ -0,0 +1,8 @@
+mod merge;
+mod sort;
+mod summit;
+mod utils;
+
+pub use merge::merge;
+pub use sort::sort;
+pub use summit::summit;
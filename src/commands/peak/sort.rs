@@ -0,0 +1,16 @@
+use super::utils::read_peaks;
+use crate::{cli::peak::PeakSortArgs, io::write_records_iter};
+use anyhow::Result;
+
+/// Sorts the peaks from the highest to the lowest value of a statistic
+pub fn sort(args: PeakSortArgs) -> Result<()> {
+    let bed_reader = args.input.get_reader()?;
+    let (mut peaks, _) = read_peaks(bed_reader)?;
+    let by = args.params.by;
+
+    // A stable sort keeps the input order of tied peaks
+    peaks.sort_by(|a, b| by.value(b).total_cmp(&by.value(a)));
+
+    let writer = args.output.get_writer()?;
+    write_records_iter(peaks.into_iter(), writer)
+}
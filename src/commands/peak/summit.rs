@@ -0,0 +1,29 @@
+use super::utils::read_peaks;
+use crate::{
+    cli::peak::SummitArgs,
+    io::write_records_iter,
+    types::{Genome, Translater},
+};
+use anyhow::{bail, Result};
+
+/// Re-centres every narrowPeak record on its summit
+pub fn summit(args: SummitArgs) -> Result<()> {
+    let bed_reader = args.input.get_reader()?;
+    let (mut peaks, narrow) = read_peaks(bed_reader)?;
+    if !narrow {
+        bail!("broadPeak records have no summit to re-centre on");
+    }
+    let mut translater = Translater::new();
+    for peak in peaks.iter() {
+        translater.add_name(&peak.chr);
+    }
+    let genome =
+        Genome::from_opt_path_immutable_with(args.params.genome, Some(&translater), false)?;
+    for peak in peaks.iter_mut() {
+        if let Some(summit) = peak.summit()? {
+            peak.recenter(summit, args.params.extend, genome.as_ref());
+        }
+    }
+    let writer = args.output.get_writer()?;
+    write_records_iter(peaks.into_iter(), writer)
+}
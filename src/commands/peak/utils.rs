@@ -0,0 +1,27 @@
+use crate::{
+    io::{build_reader, BedReader},
+    types::{InputFormat, Peak},
+};
+use anyhow::{bail, Result};
+use csv::ByteRecord;
+
+/// Reads every record of a narrowPeak or broadPeak file
+///
+/// Returns the peaks and whether they are narrowPeak records.
+pub fn read_peaks(bed_reader: BedReader) -> Result<(Vec<Peak>, bool)> {
+    let narrow = match bed_reader.input_format() {
+        InputFormat::NarrowPeak => true,
+        InputFormat::BroadPeak => false,
+        format => bail!(
+            "Peak intervals (narrowPeak or broadPeak) are required but found {:?}",
+            format
+        ),
+    };
+    let mut reader = build_reader(bed_reader.reader());
+    let mut raw_record = ByteRecord::new();
+    let mut peaks = Vec::new();
+    while reader.read_byte_record(&mut raw_record)? {
+        peaks.push(Peak::from_byte_record(&raw_record, narrow)?);
+    }
+    Ok((peaks, narrow))
+}
@@ -27,7 +27,10 @@ macro_rules! dispatch_to_lhs_multi_rhs {
             (Bed6, bed6_set_with),
             (Bed12, bed12_set_with),
             (Gtf, gtf_set_with),
+            (Gff, gtf_set_with),
             (Ambiguous, meta_interval_set_with),
+            (NarrowPeak, meta_interval_set_with),
+            (BroadPeak, meta_interval_set_with),
             (BedGraph, bedgraph_set_with)
         )
     };
@@ -48,8 +51,11 @@ macro_rules! dispatch_to_multi_rhs {
             (Bed6, into_bed6_set_with),
             (Bed12, into_bed12_set_with),
             (Gtf, into_gtf_set_with),
+            (Gff, into_gtf_set_with),
             (BedGraph, into_bedgraph_set_with),
-            (Ambiguous, into_meta_interval_set_with)
+            (Ambiguous, into_meta_interval_set_with),
+            (NarrowPeak, into_meta_interval_set_with),
+            (BroadPeak, into_meta_interval_set_with)
         )
     };
 }
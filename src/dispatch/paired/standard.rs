@@ -25,7 +25,10 @@ macro_rules! dispatch_to_lhs {
             (Bed6, bed6_set_with),
             (Bed12, bed12_set_with),
             (Gtf, gtf_set_with),
+            (Gff, gtf_set_with),
             (Ambiguous, meta_interval_set_with),
+            (NarrowPeak, meta_interval_set_with),
+            (BroadPeak, meta_interval_set_with),
             (BedGraph, bedgraph_set_with)
         )
     };
@@ -46,7 +49,10 @@ macro_rules! dispatch_to_rhs {
             (Bed6, bed6_set_with),
             (Bed12, bed12_set_with),
             (Gtf, gtf_set_with),
+            (Gff, gtf_set_with),
             (Ambiguous, meta_interval_set_with),
+            (NarrowPeak, meta_interval_set_with),
+            (BroadPeak, meta_interval_set_with),
             (BedGraph, bedgraph_set_with)
         )
     };
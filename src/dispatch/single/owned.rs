@@ -13,7 +13,10 @@ macro_rules! dispatch_single {
             (Bed6, bed6_set),
             (Bed12, bed12_set),
             (Gtf, gtf_set),
+            (Gff, gtf_set),
             (Ambiguous, meta_interval_set),
+            (NarrowPeak, meta_interval_set),
+            (BroadPeak, meta_interval_set),
             (BedGraph, bedgraph_set)
         )
     }};
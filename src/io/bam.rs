@@ -0,0 +1,83 @@
+use anyhow::Result;
+use rust_htslib::bam::{
+    FetchDefinition, HeaderView, IndexedReader as IndexedBamReader, Read, Reader as BamReader,
+    Record,
+};
+
+/// A BAM reader that only visits the records overlapping a set of regions
+/// when an index is available, and falls back to streaming the full file otherwise.
+pub enum RegionBamReader {
+    Stream(BamReader),
+    Indexed(IndexedBamReader),
+}
+impl RegionBamReader {
+    pub fn header(&self) -> &HeaderView {
+        match self {
+            Self::Stream(reader) => reader.header(),
+            Self::Indexed(reader) => reader.header(),
+        }
+    }
+
+    pub fn set_threads(&mut self, n_threads: usize) -> Result<()> {
+        match self {
+            Self::Stream(reader) => reader.set_threads(n_threads)?,
+            Self::Indexed(reader) => reader.set_threads(n_threads)?,
+        }
+        Ok(())
+    }
+
+    /// Calls `func` on every record overlapping the provided regions
+    ///
+    /// Regions are `(tid, start, end)` tuples that must be sorted and non-overlapping.
+    /// If no regions are provided (or the reader is not indexed) every record in the
+    /// file is visited.
+    ///
+    /// A record spanning multiple regions is only visited once: any record starting
+    /// before the end of the previous region on the same chromosome must overlap that
+    /// region as well and was therefore already visited.
+    pub fn for_each_record<F>(
+        &mut self,
+        regions: Option<&[(u32, usize, usize)]>,
+        mut func: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&Record) -> Result<()>,
+    {
+        // Initialize an empty record to avoid repeated allocations
+        let mut record = Record::new();
+        match (self, regions) {
+            (Self::Indexed(reader), Some(regions)) => {
+                let mut last_region: Option<(u32, usize)> = None;
+                for &(tid, start, end) in regions {
+                    reader.fetch((tid, start as i64, end as i64))?;
+                    let last_end = match last_region {
+                        Some((last_tid, last_end)) if last_tid == tid => Some(last_end),
+                        _ => None,
+                    };
+                    while let Some(result) = reader.read(&mut record) {
+                        result?;
+                        if last_end.is_some_and(|last_end| (record.pos() as usize) < last_end) {
+                            continue;
+                        }
+                        func(&record)?;
+                    }
+                    last_region = Some((tid, end));
+                }
+            }
+            (Self::Indexed(reader), None) => {
+                reader.fetch(FetchDefinition::All)?;
+                while let Some(result) = reader.read(&mut record) {
+                    result?;
+                    func(&record)?;
+                }
+            }
+            (Self::Stream(reader), _) => {
+                while let Some(result) = reader.read(&mut record) {
+                    result?;
+                    func(&record)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
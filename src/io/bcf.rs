@@ -0,0 +1,76 @@
+use anyhow::Result;
+use rust_htslib::bcf::{
+    header::HeaderView, IndexedReader as IndexedBcfReader, Read, Reader as BcfReader, Record,
+};
+
+/// A BCF/VCF reader that only visits the records overlapping a set of regions
+/// when an index is available, and falls back to streaming the full file otherwise.
+pub enum RegionBcfReader {
+    Stream(BcfReader),
+    Indexed(IndexedBcfReader),
+}
+impl RegionBcfReader {
+    pub fn header(&self) -> &HeaderView {
+        match self {
+            Self::Stream(reader) => reader.header(),
+            Self::Indexed(reader) => reader.header(),
+        }
+    }
+
+    /// Calls `func` on every record overlapping the provided regions
+    ///
+    /// Regions are `(rid, start, end)` tuples that must be sorted and non-overlapping.
+    /// If no regions are provided (or the reader is not indexed) every record in the
+    /// file is visited.
+    ///
+    /// A record spanning multiple regions is only visited once: any record starting
+    /// before the end of the previous region on the same chromosome must overlap that
+    /// region as well and was therefore already visited.
+    pub fn for_each_record<F>(
+        &mut self,
+        regions: Option<&[(u32, usize, usize)]>,
+        mut func: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&Record) -> Result<()>,
+    {
+        match (self, regions) {
+            (Self::Indexed(reader), Some(regions)) => {
+                // Initialize an empty record to avoid repeated allocations
+                let mut record = reader.empty_record();
+                let mut last_region: Option<(u32, usize)> = None;
+                for &(rid, start, end) in regions {
+                    // The fetched end is inclusive
+                    reader.fetch(rid, start as u64, Some(end.saturating_sub(1) as u64))?;
+                    let last_end = match last_region {
+                        Some((last_rid, last_end)) if last_rid == rid => Some(last_end),
+                        _ => None,
+                    };
+                    while let Some(result) = reader.read(&mut record) {
+                        result?;
+                        if last_end.is_some_and(|last_end| (record.pos() as usize) < last_end) {
+                            continue;
+                        }
+                        func(&record)?;
+                    }
+                    last_region = Some((rid, end));
+                }
+            }
+            (Self::Indexed(reader), None) => {
+                let mut record = reader.empty_record();
+                while let Some(result) = reader.read(&mut record) {
+                    result?;
+                    func(&record)?;
+                }
+            }
+            (Self::Stream(reader), _) => {
+                let mut record = reader.empty_record();
+                while let Some(result) = reader.read(&mut record) {
+                    result?;
+                    func(&record)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
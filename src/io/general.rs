@@ -1,11 +1,16 @@
+use super::{RegionBamReader, RegionBcfReader};
 use anyhow::{bail, Result};
 use gzp::deflate::Bgzf;
 use gzp::{Compression, ZBuilder};
+use hashbrown::HashMap;
 use niffler::get_reader;
 use rust_htslib::{
-    bam::{Format as SamFormat, Header, HeaderView, Reader as BamReader, Writer as BamWriter},
+    bam::{
+        Format as SamFormat, Header, HeaderView, IndexedReader as IndexedBamReader, Read,
+        Reader as BamReader, Writer as BamWriter,
+    },
     bcf::{
-        header::HeaderView as BcfHeaderView, Format as BcfFormat, Header as BcfHeader,
+        Format as BcfFormat, Header as BcfHeader, IndexedReader as IndexedBcfReader,
         Reader as BcfReader, Writer as BcfWriter,
     },
 };
@@ -39,10 +44,85 @@ pub fn match_input(input: Option<String>) -> Result<Box<dyn BufRead>> {
     }
 }
 
-pub fn match_bam_input(input: Option<String>) -> Result<BamReader> {
+/// Checks that every contig of the header is in the reference FASTA index with the same length
+///
+/// CRAM records are encoded against the reference sequence, so a mismatched
+/// reference would silently decode (or encode) the wrong bases.
+fn validate_reference(reference: &str, header: &HeaderView) -> Result<()> {
+    let fai_path = format!("{}.fai", reference);
+    let fai = match File::open(&fai_path) {
+        Ok(file) => BufReader::new(file),
+        Err(_) => bail!(
+            "Reference FASTA must be indexed (missing {}) - try `samtools faidx {}`",
+            fai_path,
+            reference
+        ),
+    };
+    let mut contigs = HashMap::new();
+    for line in fai.lines() {
+        let line = line?;
+        let mut fields = line.split('\t');
+        if let (Some(name), Some(length)) = (fields.next(), fields.next()) {
+            contigs.insert(name.to_string(), length.parse::<u64>()?);
+        }
+    }
+    for tid in 0..header.target_count() {
+        let name = String::from_utf8_lossy(header.tid2name(tid));
+        let length = header.target_len(tid).unwrap_or(0);
+        match contigs.get(name.as_ref()) {
+            Some(&fai_length) if fai_length == length => {}
+            Some(&fai_length) => bail!(
+                "Contig {} has length {} in the header but {} in the reference {}",
+                name,
+                length,
+                fai_length,
+                reference
+            ),
+            None => bail!(
+                "Contig {} is missing from the reference {}",
+                name,
+                reference
+            ),
+        }
+    }
+    Ok(())
+}
+
+pub fn match_bam_input(input: Option<String>, reference: Option<String>) -> Result<BamReader> {
+    let mut reader = match input {
+        Some(filename) => BamReader::from_path(filename)?,
+        None => BamReader::from_stdin()?,
+    };
+    if let Some(reference) = reference {
+        validate_reference(&reference, reader.header())?;
+        reader.set_reference(reference)?;
+    }
+    Ok(reader)
+}
+
+/// Checks for a `.bai`, `.csi`, or `.crai` index next to the BAM/CRAM file
+fn has_bam_index(filename: &str) -> bool {
+    ["bai", "csi", "crai"].iter().any(|ext| {
+        Path::new(&format!("{}.{}", filename, ext)).exists()
+            || Path::new(filename).with_extension(ext).exists()
+    })
+}
+
+/// Opens an indexed reader if the BAM file has an index, otherwise falls back to streaming
+pub fn match_bam_region_input(
+    input: Option<String>,
+    reference: Option<String>,
+) -> Result<RegionBamReader> {
     match input {
-        Some(filename) => Ok(BamReader::from_path(filename)?),
-        None => Ok(BamReader::from_stdin()?),
+        Some(filename) if has_bam_index(&filename) => {
+            let mut reader = IndexedBamReader::from_path(filename)?;
+            if let Some(reference) = reference {
+                validate_reference(&reference, reader.header())?;
+                reader.set_reference(reference)?;
+            }
+            Ok(RegionBamReader::Indexed(reader))
+        }
+        input => Ok(RegionBamReader::Stream(match_bam_input(input, reference)?)),
     }
 }
 
@@ -53,6 +133,23 @@ pub fn match_bcf_input(input: Option<String>) -> Result<BcfReader> {
     }
 }
 
+/// Checks for a `.csi` or `.tbi` index next to the BCF/VCF file
+fn has_bcf_index(filename: &str) -> bool {
+    ["csi", "tbi"]
+        .iter()
+        .any(|ext| Path::new(&format!("{}.{}", filename, ext)).exists())
+}
+
+/// Opens an indexed reader if the BCF/VCF file has an index, otherwise falls back to streaming
+pub fn match_bcf_region_input(input: Option<String>) -> Result<RegionBcfReader> {
+    match input {
+        Some(filename) if has_bcf_index(&filename) => Ok(RegionBcfReader::Indexed(
+            IndexedBcfReader::from_path(filename)?,
+        )),
+        input => Ok(RegionBcfReader::Stream(match_bcf_input(input)?)),
+    }
+}
+
 fn compression_aware_write_buffer(
     filename: String,
     compression_threads: usize,
@@ -114,32 +211,65 @@ pub fn match_bam_output(
     header: &HeaderView,
     format: SamFormat,
     n_threads: usize,
+    reference: Option<String>,
 ) -> Result<BamWriter> {
+    if let Some(reference) = &reference {
+        validate_reference(reference, header)?;
+    }
+    let template = match (&reference, format) {
+        (Some(reference), SamFormat::Cram) => with_reference_url(header, reference)?,
+        _ => Header::from_template(header),
+    };
     let mut writer = if let Some(filename) = path {
-        BamWriter::from_path(filename, &Header::from_template(header), format)
+        BamWriter::from_path(filename, &template, format)
     } else {
-        BamWriter::from_stdout(&Header::from_template(header), format)
+        BamWriter::from_stdout(&template, format)
     }?;
     writer.set_threads(n_threads)?;
+    if let Some(reference) = reference {
+        writer.set_reference(reference)?;
+    }
     Ok(writer)
 }
 
+/// Points the `@SQ` lines of a header at the reference with `UR` tags
+///
+/// The CRAM header is written as soon as the writer is opened, so the
+/// reference must be discoverable from the header itself or htslib falls
+/// back to embedding the reference in the output.
+fn with_reference_url(header: &HeaderView, reference: &str) -> Result<Header> {
+    let url = format!("UR:file:{}", std::fs::canonicalize(reference)?.display());
+    let text = String::from_utf8_lossy(header.as_bytes());
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        if line.starts_with("@SQ") {
+            let mut fields: Vec<&str> = line
+                .split('\t')
+                .filter(|field| !field.starts_with("UR:"))
+                .collect();
+            fields.push(&url);
+            lines.push(fields.join("\t"));
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    let text = lines.join("\n") + "\n";
+    Ok(Header::from_template(&HeaderView::from_bytes(
+        text.as_bytes(),
+    )))
+}
+
 pub fn match_bcf_output(
     path: Option<String>,
-    header: &BcfHeaderView,
+    header: &BcfHeader,
     format: BcfFormat,
     compressed: bool,
     n_threads: usize,
 ) -> Result<BcfWriter> {
     let mut writer = if let Some(filename) = path {
-        BcfWriter::from_path(
-            filename,
-            &BcfHeader::from_template(header),
-            !compressed,
-            format,
-        )
+        BcfWriter::from_path(filename, header, !compressed, format)
     } else {
-        BcfWriter::from_stdout(&BcfHeader::from_template(header), !compressed, format)
+        BcfWriter::from_stdout(header, !compressed, format)
     }?;
     writer.set_threads(n_threads)?;
     Ok(writer)
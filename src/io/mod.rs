@@ -1,10 +1,14 @@
+mod bam;
+mod bcf;
 mod general;
 mod iter;
 pub mod read;
 mod write;
+pub use bam::RegionBamReader;
+pub use bcf::RegionBcfReader;
 pub use general::{
-    match_bam_input, match_bam_output, match_bcf_input, match_bcf_output, match_input,
-    match_output, match_output_mt,
+    match_bam_input, match_bam_output, match_bam_region_input, match_bcf_input, match_bcf_output,
+    match_bcf_region_input, match_input, match_output, match_output_mt,
 };
 pub use iter::{NamedIter, UnnamedIter};
 pub use read::{build_reader, iter_unnamed, BedReader};
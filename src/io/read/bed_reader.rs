@@ -10,10 +10,11 @@ use crate::{
     create_io,
     types::{
         Bed12Set, Bed3Set, Bed4Set, Bed6Set, BedGraphSet, FieldFormat, GtfSet, InputFormat,
-        MetaIntervalSet, SplitTranslater,
+        MetaIntervalSet, NumericBed6, SplitTranslater,
     },
 };
-use anyhow::Result;
+use anyhow::{bail, Result};
+use bedrs::{Coordinates, Score, Strand};
 use flate2::read::MultiGzDecoder;
 use gzp::BgzfSyncReader;
 use std::{
@@ -46,6 +47,24 @@ impl BedReader {
         self.field_format == FieldFormat::StringBased
     }
 
+    /// Checks whether the chromosome names are strings, ignoring any other fields
+    ///
+    /// BED3+N and peak inputs are always read as named to keep their payload, but
+    /// can still be streamed as numeric BED3 when their chromosomes are integers.
+    pub fn has_named_chr(&self) -> Result<bool> {
+        let field_format = FieldFormat::predict(&self.reader, InputFormat::Bed3)?;
+        Ok(field_format == FieldFormat::StringBased)
+    }
+
+    /// Reads the fields as strings regardless of the predicted field format
+    ///
+    /// Integer chromosome names can always be read as strings, so this
+    /// lets named and unnamed inputs be processed together.
+    pub fn into_named(mut self) -> Self {
+        self.field_format = FieldFormat::StringBased;
+        self
+    }
+
     /// Reads a BED file from a path and autodetects the compression and format
     pub fn from_path(
         input: Option<String>,
@@ -109,6 +128,100 @@ impl BedReader {
         ))
     }
 
+    /// Reads the intervals as BED6 so that every supported format shares the
+    /// same name, score, and strand fields
+    ///
+    /// The fields are always read as strings so the translater is always
+    /// present. BED3 and bedGraph intervals have no name and are given a
+    /// placeholder name index that should not be read.
+    pub fn bed6_set_named(self) -> Result<(Bed6Set, SplitTranslater)> {
+        let reader = self.into_named();
+        let (set, translater) = match reader.input_format() {
+            InputFormat::Bed3 => {
+                let (set, translater) = reader.bed3_set()?;
+                let records = set
+                    .records()
+                    .iter()
+                    .map(|iv| {
+                        NumericBed6::new(
+                            *iv.chr(),
+                            iv.start(),
+                            iv.end(),
+                            0,
+                            Score(None),
+                            Strand::Unknown,
+                        )
+                    })
+                    .collect();
+                (Bed6Set::from_unsorted(records), translater)
+            }
+            InputFormat::Bed4 => {
+                let (set, translater) = reader.bed4_set()?;
+                let records = set
+                    .records()
+                    .iter()
+                    .map(|iv| {
+                        NumericBed6::new(
+                            *iv.chr(),
+                            iv.start(),
+                            iv.end(),
+                            *iv.name(),
+                            Score(None),
+                            Strand::Unknown,
+                        )
+                    })
+                    .collect();
+                (Bed6Set::from_unsorted(records), translater)
+            }
+            // The peak columns following the strand are not needed
+            InputFormat::Bed6 | InputFormat::NarrowPeak | InputFormat::BroadPeak => {
+                reader.bed6_set()?
+            }
+            InputFormat::Bed12 => {
+                let (set, translater) = reader.bed12_set()?;
+                let records = set
+                    .records()
+                    .iter()
+                    .map(|iv| {
+                        NumericBed6::new(
+                            *iv.chr(),
+                            iv.start(),
+                            iv.end(),
+                            *iv.name(),
+                            iv.score(),
+                            iv.strand().unwrap_or_default(),
+                        )
+                    })
+                    .collect();
+                (Bed6Set::from_unsorted(records), translater)
+            }
+            InputFormat::BedGraph => {
+                let (set, translater) = reader.bedgraph_set()?;
+                let records = set
+                    .records()
+                    .iter()
+                    .map(|iv| {
+                        NumericBed6::new(
+                            *iv.chr(),
+                            iv.start(),
+                            iv.end(),
+                            0,
+                            Score(Some(iv.score())),
+                            Strand::Unknown,
+                        )
+                    })
+                    .collect();
+                (Bed6Set::from_unsorted(records), translater)
+            }
+            format => bail!(
+                "BED intervals (BED3, BED4, BED6, BED12, or bedGraph) are required but found {:?}",
+                format
+            ),
+        };
+        // Reading the fields as strings always builds a translater
+        Ok((set, translater.unwrap()))
+    }
+
     create_io!(bed3, Bed3Set);
     create_io!(bed4, Bed4Set);
     create_io!(bed6, Bed6Set);
@@ -7,22 +7,34 @@ mod utils;
 
 use anyhow::Result;
 use clap::Parser;
-use cli::{bam::BamCommand, bcf::BcfCommand, Cli, Command};
+use cli::{
+    bam::BamCommand, bcf::BcfCommand, gff::GffCommand, gtf::GtfCommand, peak::PeakCommand, Cli,
+    Command,
+};
 use commands::{
-    bam, bcf, closest, cluster, complement, coverage, extend, flank, get_fasta, intersect, join,
-    merge, random, sample, segment, shift, sort, spacing, subtract, unionbedgraph, window,
+    bam, bcf, closest, cluster, complement, coverage, extend, flank, get_fasta, gff, gtf,
+    intersect, join, merge, peak, random, sample, segment, shift, sort, spacing, subtract,
+    unionbedgraph, window,
 };
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
     match cli.command {
         Command::Bam(command) => match command {
+            BamCommand::Annotate(args) => bam::annotate(args)?,
             BamCommand::Convert(args) => bam::convert(args)?,
             BamCommand::Filter(args) => bam::filter(args)?,
+            BamCommand::Count(args) => bam::count(args)?,
             BamCommand::Coverage(args) => bam::coverage(args)?,
+            BamCommand::Genomecov(args) => bam::genomecov(args)?,
+            BamCommand::Stats(args) => bam::stats(args)?,
         },
         Command::Bcf(command) => match command {
+            BcfCommand::Annotate(args) => bcf::annotate(args)?,
+            BcfCommand::Convert(args) => bcf::convert(args)?,
+            BcfCommand::Coverage(args) => bcf::coverage(args)?,
             BcfCommand::Filter(args) => bcf::filter(args)?,
+            BcfCommand::Intersect(args) => bcf::intersect(args)?,
         },
         Command::Closest(args) => closest(args)?,
         Command::Cluster(args) => cluster(args)?,
@@ -31,9 +43,26 @@ fn main() -> Result<()> {
         Command::Extend(args) => extend(args)?,
         Command::Flank(args) => flank(args)?,
         Command::GetFasta(args) => get_fasta(args)?,
+        Command::Gff(command) => match command {
+            GffCommand::Convert(args) => gff::convert(args)?,
+        },
+        Command::Gtf(command) => match command {
+            GtfCommand::Filter(args) => gtf::filter(args)?,
+            GtfCommand::Introns(args) => gtf::introns(args)?,
+            GtfCommand::Promoters(args) => gtf::promoters(args)?,
+            GtfCommand::Tes(args) => gtf::tes(args)?,
+            GtfCommand::Transcripts(args) => gtf::transcripts(args)?,
+            GtfCommand::Tss(args) => gtf::tss(args)?,
+            GtfCommand::Utrs(args) => gtf::utrs(args)?,
+        },
         Command::Intersect(args) => intersect(args)?,
         Command::Join(args) => join(args)?,
         Command::Merge(args) => merge(args)?,
+        Command::Peak(command) => match command {
+            PeakCommand::Merge(args) => peak::merge(args)?,
+            PeakCommand::Sort(args) => peak::sort(args)?,
+            PeakCommand::Summit(args) => peak::summit(args)?,
+        },
         Command::Random(args) => random(args)?,
         Command::Sample(args) => sample(args)?,
         Command::Segment(args) => segment(args)?,
@@ -14,9 +14,21 @@ pub enum InputFormat {
     Bed4,
     Bed6,
     Gtf,
+    /// GFF3 with `key=value` attributes
+    #[value(alias = "gff3")]
+    Gff,
     Bed12,
+    /// BED3+N: the first three columns are the interval and any remaining
+    /// columns are carried through as an opaque payload
+    #[value(alias = "bed3+n")]
     Ambiguous,
     BedGraph,
+    /// ENCODE narrowPeak (BED6+4) with a summit offset
+    #[value(alias = "narrowpeak")]
+    NarrowPeak,
+    /// ENCODE broadPeak (BED6+3)
+    #[value(alias = "broadpeak")]
+    BroadPeak,
 }
 impl InputFormat {
     /// Returns a rank for the input format.
@@ -51,19 +63,95 @@ impl InputFormat {
         } else {
             bail!("File missing newline, cannot predict input format")
         };
-        let num_fields = first.split(|b| *b == b'\t').count();
-        match num_fields {
+        let fields = first.split(|b| *b == b'\t').collect::<Vec<_>>();
+        match fields.len() {
             1..=2 => bail!("Too few fields in line: {}", from_utf8(first)?),
             3 => Ok(InputFormat::Bed3),
             4 => Ok(InputFormat::Bed4),
-            6 => Ok(InputFormat::Bed6),
-            9 => Ok(InputFormat::Gtf),
-            12 => Ok(InputFormat::Bed12),
+            6 if has_bed6_fields(&fields) => Ok(InputFormat::Bed6),
+            9 if has_peak_fields(&fields) && !has_bed9_fields(&fields) => {
+                Ok(InputFormat::BroadPeak)
+            }
+            10 if has_peak_fields(&fields) && is_integer(fields[9]) => Ok(InputFormat::NarrowPeak),
+            // BED9 files have their coordinates in the second and third fields
+            9 if (!is_integer(fields[1]) || !is_integer(fields[2]))
+                && has_gff_attributes(fields[8]) =>
+            {
+                Ok(InputFormat::Gff)
+            }
+            9 if !is_integer(fields[1]) || !is_integer(fields[2]) => Ok(InputFormat::Gtf),
+            12 if has_bed12_fields(&fields) => Ok(InputFormat::Bed12),
             _ => Ok(InputFormat::Ambiguous),
         }
     }
 }
 
+/// Checks whether a field can be parsed as an integer
+fn is_integer(field: &[u8]) -> bool {
+    from_utf8(field).is_ok_and(|f| f.parse::<i64>().is_ok())
+}
+
+/// Checks whether a field can be parsed as a float
+fn is_float(field: &[u8]) -> bool {
+    from_utf8(field).is_ok_and(|f| f.parse::<f64>().is_ok())
+}
+
+/// Checks whether the score and strand fields of a line are valid BED6 fields
+///
+/// Lines with other values in these positions are BED3+N
+fn has_bed6_fields(fields: &[&[u8]]) -> bool {
+    let score = fields[4];
+    let valid_score = score == b"." || from_utf8(score).is_ok_and(|f| f.parse::<f64>().is_ok());
+    let valid_strand = matches!(fields[5], b"+" | b"-" | b".");
+    valid_score && valid_strand
+}
+
+/// Checks whether the signalValue, pValue, and qValue fields of a line are valid peak fields
+fn has_peak_fields(fields: &[&[u8]]) -> bool {
+    has_bed6_fields(fields) && fields[6..9].iter().all(|f| is_float(f))
+}
+
+/// Checks whether the thick coordinates and itemRgb of a line are valid BED9 fields
+///
+/// The thick coordinates must be within the interval and the itemRgb is either a
+/// single integer or an `r,g,b` triplet. BED9 lines like these would otherwise
+/// pass as broadPeak lines.
+fn has_bed9_fields(fields: &[&[u8]]) -> bool {
+    let parse = |field: &[u8]| from_utf8(field).ok()?.parse::<i64>().ok();
+    let (Some(start), Some(end)) = (parse(fields[1]), parse(fields[2])) else {
+        return false;
+    };
+    let valid_thick = [fields[6], fields[7]]
+        .iter()
+        .all(|f| parse(f).is_some_and(|pos| (start..=end).contains(&pos)));
+    let valid_rgb = is_integer(fields[8]) || {
+        let channels = fields[8].split(|b| *b == b',').collect::<Vec<_>>();
+        channels.len() == 3 && channels.iter().all(|c| is_integer(c))
+    };
+    valid_thick && valid_rgb
+}
+
+/// Checks whether an attribute column uses the GFF3 `key=value` syntax
+///
+/// GTF attributes are `key "value"` pairs and never have an `=` in their key
+fn has_gff_attributes(field: &[u8]) -> bool {
+    from_utf8(field).is_ok_and(|f| {
+        f.split(';')
+            .map(|attr| attr.trim())
+            .find(|attr| !attr.is_empty())
+            .and_then(|attr| attr.split_once('='))
+            .is_some_and(|(key, _)| !key.is_empty() && !key.contains([' ', '"']))
+    })
+}
+
+/// Checks whether the thick coordinates and block count of a line are valid BED12 fields
+fn has_bed12_fields(fields: &[&[u8]]) -> bool {
+    has_bed6_fields(fields)
+        && is_integer(fields[6])
+        && is_integer(fields[7])
+        && is_integer(fields[9])
+}
+
 /// Determines the field format of a file or stream.
 ///
 /// Will read the first line of the file and try to parse the first and fourth fields as integers.
@@ -106,7 +194,7 @@ impl FieldFormat {
                     Ok(FieldFormat::IntegerBased)
                 }
             }
-            InputFormat::Gtf => {
+            InputFormat::Gtf | InputFormat::Gff => {
                 let seqname = from_utf8(fields[0])?;
                 let source = from_utf8(fields[1])?;
                 let feature = from_utf8(fields[2])?;
@@ -121,16 +209,10 @@ impl FieldFormat {
                     Ok(FieldFormat::IntegerBased)
                 }
             }
-            InputFormat::Ambiguous => {
-                let all_int = fields
-                    .iter()
-                    .filter_map(|f| from_utf8(f).ok())
-                    .all(|f| f.parse::<u32>().is_ok());
-                if all_int {
-                    Ok(FieldFormat::IntegerBased)
-                } else {
-                    Ok(FieldFormat::StringBased)
-                }
+            // The payload of BED3+N lines (and the peak columns, which are carried
+            // through the same way) can only be kept as a string
+            InputFormat::Ambiguous | InputFormat::NarrowPeak | InputFormat::BroadPeak => {
+                Ok(FieldFormat::StringBased)
             }
         }
     }
@@ -197,6 +279,87 @@ mod testing {
         assert_eq!(input_format, InputFormat::Ambiguous);
     }
 
+    #[test]
+    fn input_format_bed6_plus() {
+        let line = b"chr1\t1\t2\tname\tgene\tprotein_coding";
+        let mut buffer = BufReader::new(line.as_slice());
+        buffer.fill_buf().unwrap();
+        let input_format = InputFormat::predict(&buffer).unwrap();
+        assert_eq!(input_format, InputFormat::Ambiguous);
+    }
+
+    #[test]
+    fn input_format_bed9() {
+        let line = b"chr1\t1\t2\tname\t0\t+\t1\t2\t255,0,0";
+        let mut buffer = BufReader::new(line.as_slice());
+        buffer.fill_buf().unwrap();
+        let input_format = InputFormat::predict(&buffer).unwrap();
+        assert_eq!(input_format, InputFormat::Ambiguous);
+    }
+
+    #[test]
+    fn input_format_bed9_zero_rgb() {
+        let line = b"chr1\t1\t200\tname\t0\t+\t50\t150\t0";
+        let mut buffer = BufReader::new(line.as_slice());
+        buffer.fill_buf().unwrap();
+        let input_format = InputFormat::predict(&buffer).unwrap();
+        assert_eq!(input_format, InputFormat::Ambiguous);
+    }
+
+    #[test]
+    fn input_format_gtf() {
+        let line = b"chr1\tsource\tgene\t1\t2\t.\t+\t.\tgene_id \"a\";";
+        let mut buffer = BufReader::new(line.as_slice());
+        buffer.fill_buf().unwrap();
+        let input_format = InputFormat::predict(&buffer).unwrap();
+        assert_eq!(input_format, InputFormat::Gtf);
+    }
+
+    #[test]
+    fn input_format_gff() {
+        let line = b"chr1\tsource\tgene\t1\t2\t.\t+\t.\tID=gene1;Name=A%3BB";
+        let mut buffer = BufReader::new(line.as_slice());
+        buffer.fill_buf().unwrap();
+        let input_format = InputFormat::predict(&buffer).unwrap();
+        assert_eq!(input_format, InputFormat::Gff);
+    }
+
+    #[test]
+    fn input_format_gtf_with_equals() {
+        let line = b"chr1\tsource\tgene\t1\t2\t.\t+\t.\tgene_id \"a=b\";";
+        let mut buffer = BufReader::new(line.as_slice());
+        buffer.fill_buf().unwrap();
+        let input_format = InputFormat::predict(&buffer).unwrap();
+        assert_eq!(input_format, InputFormat::Gtf);
+    }
+
+    #[test]
+    fn input_format_narrow_peak() {
+        let line = b"chr1\t1\t200\tpeak_1\t85\t.\t5.2\t12.1\t8.5\t95";
+        let mut buffer = BufReader::new(line.as_slice());
+        buffer.fill_buf().unwrap();
+        let input_format = InputFormat::predict(&buffer).unwrap();
+        assert_eq!(input_format, InputFormat::NarrowPeak);
+    }
+
+    #[test]
+    fn input_format_broad_peak() {
+        let line = b"chr1\t1\t200\tpeak_1\t85\t.\t5.2\t12.1\t8.5";
+        let mut buffer = BufReader::new(line.as_slice());
+        buffer.fill_buf().unwrap();
+        let input_format = InputFormat::predict(&buffer).unwrap();
+        assert_eq!(input_format, InputFormat::BroadPeak);
+    }
+
+    #[test]
+    fn field_format_ambiguous() {
+        let line = b"1\t1\t2\t3\t4";
+        let mut buffer = BufReader::new(line.as_slice());
+        buffer.fill_buf().unwrap();
+        let field_format = FieldFormat::predict(&buffer, InputFormat::Ambiguous).unwrap();
+        assert_eq!(field_format, FieldFormat::StringBased);
+    }
+
     #[test]
     fn field_format_integer_based_bed3() {
         let line = b"1\t1\t2";
@@ -0,0 +1,146 @@
+use crate::utils::parse_gff_attributes;
+use anyhow::{bail, Result};
+use csv::ByteRecord;
+use hashbrown::HashMap;
+use std::{io::Read, str::from_utf8};
+
+/// Parses a single field of a GFF3 record
+fn parse_field<T: std::str::FromStr>(record: &ByteRecord, idx: usize, name: &str) -> Result<T> {
+    let field = match record.get(idx) {
+        Some(field) => from_utf8(field)?,
+        None => bail!("GFF3 record is missing the {} field", name),
+    };
+    match field.parse::<T>() {
+        Ok(value) => Ok(value),
+        Err(_) => bail!(
+            "Unable to parse the {} field of a GFF3 record: {}",
+            name,
+            field
+        ),
+    }
+}
+
+/// A single GFF3 feature with its decoded attributes
+///
+/// The source and phase columns are not kept and coordinates are kept
+/// as in the file (1-based and inclusive).
+#[derive(Debug, Clone)]
+pub struct GffRecord {
+    pub seqid: String,
+    pub feature: String,
+    pub start: usize,
+    pub end: usize,
+    pub score: String,
+    pub strand: String,
+    pub attributes: Vec<(String, Vec<String>)>,
+}
+impl GffRecord {
+    pub fn from_byte_record(record: &ByteRecord) -> Result<Self> {
+        let attributes: String = parse_field(record, 8, "attributes")?;
+        Ok(Self {
+            seqid: parse_field(record, 0, "seqid")?,
+            feature: parse_field(record, 2, "type")?,
+            start: parse_field(record, 3, "start")?,
+            end: parse_field(record, 4, "end")?,
+            score: parse_field(record, 5, "score")?,
+            strand: parse_field(record, 6, "strand")?,
+            attributes: parse_gff_attributes(&attributes),
+        })
+    }
+
+    /// Returns the first value of an attribute
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .and_then(|(_, values)| values.first())
+            .map(|v| v.as_str())
+    }
+
+    /// Returns the IDs of the parents of the feature
+    pub fn parents(&self) -> &[String] {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == "Parent")
+            .map(|(_, values)| values.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// The features of a GFF3 file linked by their `ID` and `Parent` attributes
+///
+/// Features split over multiple lines (e.g. a CDS) share an ID, which
+/// resolves to the first of its lines.
+#[derive(Debug, Default)]
+pub struct GffHierarchy {
+    records: Vec<GffRecord>,
+    ids: HashMap<String, usize>,
+}
+impl GffHierarchy {
+    /// Reads every feature of a GFF3 file
+    ///
+    /// Reading stops at an embedded `##FASTA` section.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        // The sequences of a FASTA section have a different number of fields
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .comment(Some(b'#'))
+            .flexible(true)
+            .from_reader(reader);
+        let mut raw_record = ByteRecord::new();
+        let mut hierarchy = Self::default();
+        while csv_reader.read_byte_record(&mut raw_record)? {
+            if raw_record.len() == 1 && raw_record[0].starts_with(b">") {
+                break;
+            }
+            hierarchy.push(GffRecord::from_byte_record(&raw_record)?);
+        }
+        Ok(hierarchy)
+    }
+
+    fn push(&mut self, record: GffRecord) {
+        if let Some(id) = record.attribute("ID") {
+            if !self.ids.contains_key(id) {
+                self.ids.insert(id.to_string(), self.records.len());
+            }
+        }
+        self.records.push(record);
+    }
+
+    pub fn records(&self) -> &[GffRecord] {
+        &self.records
+    }
+
+    /// Returns the index of the first parent of a feature
+    pub fn parent(&self, idx: usize) -> Option<usize> {
+        self.records[idx]
+            .parents()
+            .first()
+            .and_then(|id| self.ids.get(id))
+            .copied()
+    }
+
+    /// Returns the indices of a feature and its ancestors (following the first parent)
+    ///
+    /// Cyclic hierarchies are cut once every feature has been visited.
+    pub fn lineage(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(Some(idx), |idx| self.parent(*idx)).take(self.records.len())
+    }
+
+    /// Returns an attribute of a feature, falling back to its ancestors
+    ///
+    /// If a feature type is given only the nearest ancestor (or the
+    /// feature itself) of that type is searched.
+    pub fn resolve_attribute(&self, idx: usize, key: &str, ancestor: Option<&str>) -> Option<&str> {
+        match ancestor {
+            Some(feature) => self
+                .lineage(idx)
+                .find(|i| self.records[*i].feature == feature)
+                .and_then(|i| self.records[i].attribute(key)),
+            None => self
+                .lineage(idx)
+                .find_map(|i| self.records[i].attribute(key)),
+        }
+    }
+}
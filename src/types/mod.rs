@@ -1,14 +1,18 @@
 mod depth;
 mod formats;
+mod gff;
 mod header;
 mod pairs;
+mod peak;
 mod spacing;
 mod translate;
 use bedrs::{Bed12, Bed3, Bed4, Bed6, BedGraph, Gtf, IntervalContainer, MetaInterval};
 pub use depth::IntervalDepth;
 pub use formats::{FieldFormat, Genome, InputFormat};
+pub use gff::GffHierarchy;
 pub use header::Header;
 pub use pairs::IntervalPair;
+pub use peak::Peak;
 pub use spacing::IntervalSpacing;
 pub use translate::{
     Rename, Renamer, Reorder, SplitRetranslater, SplitTranslater, StreamTranslater, Translate,
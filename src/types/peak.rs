@@ -0,0 +1,114 @@
+use super::Genome;
+use anyhow::{bail, Result};
+use csv::ByteRecord;
+use serde::{Serialize, Serializer};
+use std::{fmt::Display, str::from_utf8};
+
+/// Writes values in their shortest form (e.g. `-1` instead of `-1.0`) as MACS2 does
+fn serialize_display<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+/// Parses a single field of a peak record
+fn parse_field<T: std::str::FromStr>(record: &ByteRecord, idx: usize, name: &str) -> Result<T> {
+    let field = match record.get(idx) {
+        Some(field) => from_utf8(field)?,
+        None => bail!("Peak record is missing the {} field", name),
+    };
+    match field.parse::<T>() {
+        Ok(value) => Ok(value),
+        Err(_) => bail!(
+            "Unable to parse the {} field of a peak record: {}",
+            name,
+            field
+        ),
+    }
+}
+
+/// A narrowPeak (BED6+4) or broadPeak (BED6+3) record
+///
+/// The name, score, and strand are carried through as-is. The peak is only
+/// defined for narrowPeak records and is the 0-based offset of the summit
+/// from the start (-1 if no summit was called).
+#[derive(Debug, Clone, Serialize)]
+pub struct Peak {
+    pub chr: String,
+    pub start: usize,
+    pub end: usize,
+    pub name: String,
+    pub score: String,
+    pub strand: String,
+    #[serde(serialize_with = "serialize_display")]
+    pub signal_value: f64,
+    #[serde(serialize_with = "serialize_display")]
+    pub p_value: f64,
+    #[serde(serialize_with = "serialize_display")]
+    pub q_value: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak: Option<i64>,
+}
+impl Peak {
+    /// Parses a peak from a narrowPeak (with a summit) or broadPeak record
+    pub fn from_byte_record(record: &ByteRecord, narrow: bool) -> Result<Self> {
+        let peak = if narrow {
+            Some(parse_field(record, 9, "peak")?)
+        } else {
+            None
+        };
+        Ok(Self {
+            chr: parse_field(record, 0, "chrom")?,
+            start: parse_field(record, 1, "chromStart")?,
+            end: parse_field(record, 2, "chromEnd")?,
+            name: parse_field(record, 3, "name")?,
+            score: parse_field(record, 4, "score")?,
+            strand: parse_field(record, 5, "strand")?,
+            signal_value: parse_field(record, 6, "signalValue")?,
+            p_value: parse_field(record, 7, "pValue")?,
+            q_value: parse_field(record, 8, "qValue")?,
+            peak,
+        })
+    }
+
+    /// Returns the absolute position of the summit
+    ///
+    /// Peaks without a called summit use their midpoint and broadPeak
+    /// records have no summit. Summits outside of the peak are an error.
+    pub fn summit(&self) -> Result<Option<usize>> {
+        match self.peak {
+            Some(offset) if offset >= 0 => {
+                if offset as usize >= self.end - self.start {
+                    bail!(
+                        "Summit offset {} is outside of the peak {}:{}-{}",
+                        offset,
+                        self.chr,
+                        self.start,
+                        self.end
+                    );
+                }
+                Ok(Some(self.start + offset as usize))
+            }
+            Some(_) => Ok(Some((self.start + self.end) / 2)),
+            None => Ok(None),
+        }
+    }
+
+    /// Re-centres the peak on a single base summit and extends it on both sides
+    ///
+    /// The peak offset is updated to point at the same summit. The end is
+    /// clamped to the chromosome size if a genome is provided.
+    pub fn recenter(&mut self, summit: usize, flank: usize, genome: Option<&Genome>) {
+        self.start = summit.saturating_sub(flank);
+        self.end = summit + flank + 1;
+        if let Some(genome) = genome {
+            let chr_idx = genome.translater().and_then(|tl| tl.get_idx(&self.chr));
+            if let Some(chr_size) = chr_idx.and_then(|idx| genome.chr_size(idx)) {
+                self.end = self.end.min(*chr_size);
+            }
+        }
+        self.peak = Some((summit - self.start) as i64);
+    }
+}
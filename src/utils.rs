@@ -22,3 +22,56 @@ pub fn sort_pairs<Ia, Ib, C, T>(
         set_b.set_sorted();
     }
 }
+
+/// Returns the value of a key in a GTF attribute string
+///
+/// Attributes are `;`-separated `key "value"` pairs, quotes are optional
+pub fn parse_gtf_attribute<'a>(attributes: &'a str, key: &str) -> Option<&'a str> {
+    attributes
+        .split(';')
+        .filter_map(|field| field.trim().split_once(' '))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.trim().trim_matches('"'))
+}
+
+/// Decodes the `%XX` escapes of a GFF3 field
+///
+/// Malformed escapes are kept as-is
+pub fn percent_decode(field: &str) -> String {
+    if !field.contains('%') {
+        return field.to_string();
+    }
+    let bytes = field.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%'
+            && idx + 2 < bytes.len()
+            && bytes[idx + 1].is_ascii_hexdigit()
+            && bytes[idx + 2].is_ascii_hexdigit()
+        {
+            let hex = std::str::from_utf8(&bytes[idx + 1..idx + 3]).unwrap();
+            decoded.push(u8::from_str_radix(hex, 16).unwrap());
+            idx += 3;
+            continue;
+        }
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Parses a GFF3 attribute string into its keys and (decoded) values
+///
+/// Attributes are `;`-separated `key=value` pairs and values may be
+/// `,`-separated lists (e.g. multiple parents)
+pub fn parse_gff_attributes(attributes: &str) -> Vec<(String, Vec<String>)> {
+    attributes
+        .split(';')
+        .filter_map(|field| field.trim().split_once('='))
+        .map(|(key, values)| {
+            let values = values.split(',').map(percent_decode).collect();
+            (percent_decode(key), values)
+        })
+        .collect()
+}
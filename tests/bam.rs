@@ -8,6 +8,14 @@ mod testing {
         output.split(|&c| c == b'\n').count()
     }
 
+    fn get_read_names(fastq: &[u8]) -> Vec<String> {
+        String::from_utf8_lossy(fastq)
+            .lines()
+            .step_by(4)
+            .map(|line| line.trim_start_matches('@').to_string())
+            .collect()
+    }
+
     fn get_num_cols(output: &[u8]) -> usize {
         output
             .split(|&c| c == b'\n')
@@ -24,6 +32,62 @@ mod testing {
             .count()
     }
 
+    #[test]
+    fn test_bam_annotate() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/filter.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("annotate")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .arg("-O")
+            .arg("sam")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        let num_header_lines = get_num_header_lines(&output.stdout);
+        assert_eq!(num_lines, 299);
+        assert_eq!(num_header_lines, 200);
+        let stdout = String::from_utf8(output.stdout)?;
+        let num_tagged = stdout.lines().filter(|l| l.contains("\tXF:Z:")).count();
+        assert_eq!(num_tagged, 14);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_annotate_tag() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/filter.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("annotate")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .arg("-T")
+            .arg("ZX")
+            .arg("-O")
+            .arg("sam")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        let num_header_lines = get_num_header_lines(&output.stdout);
+        assert_eq!(num_lines, 299);
+        assert_eq!(num_header_lines, 200);
+        let stdout = String::from_utf8(output.stdout)?;
+        let num_tagged = stdout.lines().filter(|l| l.contains("\tZX:Z:")).count();
+        assert_eq!(num_tagged, 14);
+        Ok(())
+    }
+
     #[test]
     fn test_bam_convert() -> Result<()> {
         let input = "tests/datasets/bam/tiny.bam";
@@ -64,51 +128,897 @@ mod testing {
     }
 
     #[test]
-    fn test_bam_filter() -> Result<()> {
-        let a_set = "tests/datasets/bam/tiny.bam";
-        let b_set = "tests/datasets/bam/filter.bed";
+    fn test_bam_convert_min_mapq() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
         let mut cmd = Command::cargo_bin("gia")?;
         let output = cmd
             .arg("bam")
-            .arg("filter")
-            .arg("-a")
-            .arg(a_set)
-            .arg("-b")
-            .arg(b_set)
-            .arg("-O")
-            .arg("sam") // show SAM output for easier time counting lines
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("-q")
+            .arg("10")
             .output()?;
         assert!(output.status.success());
         assert_eq!(output.stderr, b"");
         let num_lines = get_num_lines(&output.stdout);
-        let num_header_lines = get_num_header_lines(&output.stdout);
-        assert_eq!(num_lines, 215);
-        assert_eq!(num_header_lines, 200);
+        assert_eq!(num_lines, 85);
         Ok(())
     }
 
     #[test]
-    fn test_bam_filter_invert() -> Result<()> {
-        let a_set = "tests/datasets/bam/tiny.bam";
-        let b_set = "tests/datasets/bam/filter.bed";
+    fn test_bam_convert_exclude_flags() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("--exclude-flags")
+            .arg("0x100")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        assert_eq!(num_lines, 89);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_extra_columns() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("-T")
+            .arg("NH,flag,XX")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        assert_eq!(get_num_lines(&output.stdout), 99);
+        assert_eq!(get_num_cols(&output.stdout), 9);
+        let stdout = String::from_utf8(output.stdout)?;
+        assert!(stdout.lines().all(|line| line.ends_with("\t.")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_fragment_extra_columns() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("--conv")
+            .arg("fragment")
+            .arg("-T")
+            .arg("tlen")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        assert_eq!(get_num_lines(&output.stdout), 45);
+        assert_eq!(get_num_cols(&output.stdout), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_bed12() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("--conv")
+            .arg("bed12")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        let num_cols = get_num_cols(&output.stdout);
+        assert_eq!(num_lines, 99);
+        assert_eq!(num_cols, 12);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_fragment() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("--conv")
+            .arg("fragment")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        let num_cols = get_num_cols(&output.stdout);
+        assert_eq!(num_lines, 45);
+        assert_eq!(num_cols, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_bedpe() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("--conv")
+            .arg("bedpe")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        let num_cols = get_num_cols(&output.stdout);
+        assert_eq!(num_lines, 45);
+        assert_eq!(num_cols, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_fastq() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("--conv")
+            .arg("fastq")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        assert_eq!(num_lines, 353);
+        assert!(output.stdout.starts_with(b"@"));
+
+        // mates are interleaved
+        let names = get_read_names(&output.stdout);
+        for pair in names.chunks(2) {
+            assert_eq!(pair[0].strip_suffix("/1"), pair[1].strip_suffix("/2"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_fastq_include_secondary() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("--conv")
+            .arg("fastq")
+            .arg("--include-secondary")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        assert_eq!(num_lines, 393);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_fastq_paired() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let r1 = "tiny.r1.fq";
+        let r2 = "tiny.r2.fq";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("--conv")
+            .arg("fastq")
+            .arg("--r1")
+            .arg(r1)
+            .arg("--r2")
+            .arg(r2)
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let r1_output = std::fs::read(r1)?;
+        let r2_output = std::fs::read(r2)?;
+        std::fs::remove_file(r1)?;
+        std::fs::remove_file(r2)?;
+        let r1_names = get_read_names(&r1_output);
+        let r2_names = get_read_names(&r2_output);
+        assert_eq!(r1_names.len(), 44);
+        assert_eq!(r1_names.len(), r2_names.len());
+        for (r1_name, r2_name) in r1_names.iter().zip(r2_names.iter()) {
+            assert!(r1_name.ends_with("/1"));
+            assert_eq!(r1_name.strip_suffix("/1"), r2_name.strip_suffix("/2"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_fastq_singletons() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let r1 = "tiny.singletons.r1.fq";
+        let r2 = "tiny.singletons.r2.fq";
+        let singletons = "tiny.singletons.fq";
+        let mut cmd = Command::cargo_bin("gia")?;
+        // removing the reverse strand records leaves every mate without its pair
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("--conv")
+            .arg("fastq")
+            .arg("--exclude-flags")
+            .arg("16")
+            .arg("--r1")
+            .arg(r1)
+            .arg("--r2")
+            .arg(r2)
+            .arg("--singletons")
+            .arg(singletons)
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let r1_output = std::fs::read(r1)?;
+        let r2_output = std::fs::read(r2)?;
+        let singletons_output = std::fs::read(singletons)?;
+        std::fs::remove_file(r1)?;
+        std::fs::remove_file(r2)?;
+        std::fs::remove_file(singletons)?;
+        assert!(r1_output.is_empty());
+        assert!(r2_output.is_empty());
+        assert_eq!(get_read_names(&singletons_output).len(), 44);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_reference_unindexed() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let reference = "tests/datasets/bam/missing.fa";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("-R")
+            .arg(reference)
+            .output()?;
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)?.contains("must be indexed"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_reference_mismatch() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let reference = "tests/datasets/bam/mismatch.fa";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("-R")
+            .arg(reference)
+            .output()?;
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)?.contains("Contig 1 has length"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_convert_cram() -> Result<()> {
+        let bam = "tests/datasets/bam/cram.bam";
+        let cram = "tests/datasets/bam/cram.cram";
+        let reference = "tests/datasets/bam/cram.fa";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let bam_output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(bam)
+            .arg("--conv")
+            .arg("fastq")
+            .output()?;
+        let mut cmd = Command::cargo_bin("gia")?;
+        let cram_output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(cram)
+            .arg("-R")
+            .arg(reference)
+            .arg("--conv")
+            .arg("fastq")
+            .output()?;
+        assert!(bam_output.status.success());
+        assert!(cram_output.status.success());
+        assert_eq!(cram_output.stderr, b"");
+        assert_eq!(get_num_lines(&cram_output.stdout), 17);
+        assert_eq!(cram_output.stdout, bam_output.stdout);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_filter_cram_output() -> Result<()> {
+        let bam = "tests/datasets/bam/cram.bam";
+        let reference = "tests/datasets/bam/cram.fa";
+        let cram = "cram.filter.cram";
         let mut cmd = Command::cargo_bin("gia")?;
         let output = cmd
             .arg("bam")
             .arg("filter")
             .arg("-a")
-            .arg(a_set)
+            .arg(bam)
             .arg("-b")
-            .arg(b_set)
+            .arg("tests/datasets/bam/cram.bed")
             .arg("-O")
-            .arg("sam") // show SAM output for easier time counting lines
-            .arg("-v")
+            .arg("cram")
+            .arg("--output-reference")
+            .arg(reference)
+            .arg("-o")
+            .arg(cram)
             .output()?;
         assert!(output.status.success());
         assert_eq!(output.stderr, b"");
-        let num_lines = get_num_lines(&output.stdout);
+        let mut cmd = Command::cargo_bin("gia")?;
+        let cram_output = cmd
+            .arg("bam")
+            .arg("convert")
+            .arg("-i")
+            .arg(cram)
+            .arg("-R")
+            .arg(reference)
+            .output()?;
+        std::fs::remove_file(cram)?;
+        assert!(cram_output.status.success());
+        let expected = "chr1\t20\t70\tr1\t60\t+\nchr1\t45\t95\tr2\t60\t-\n";
+        assert_eq!(String::from_utf8(cram_output.stdout)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_count() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let gtf = "tests/datasets/bam/count.gtf";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("count")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-g")
+            .arg(gtf)
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let expected = "geneA\t2\ngeneB\t2\ngeneC\t1\ngeneD\t0\n";
+        assert_eq!(String::from_utf8(output.stdout)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_count_attribute_predicates() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let gtf = "tests/datasets/bam/count.gtf";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("count")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-g")
+            .arg(gtf)
+            .arg("-w")
+            .arg("transcript_id != txB")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let expected = "geneA\t2\ngeneC\t1\ngeneD\t0\n";
+        assert_eq!(String::from_utf8(output.stdout)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_count_intersection_strict() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let gtf = "tests/datasets/bam/count.gtf";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("count")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-g")
+            .arg(gtf)
+            .arg("--mode")
+            .arg("intersection-strict")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let expected = "geneA\t2\ngeneB\t2\ngeneC\t2\ngeneD\t0\n";
+        assert_eq!(String::from_utf8(output.stdout)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_count_paired() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let gtf = "tests/datasets/bam/count.gtf";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("count")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-g")
+            .arg(gtf)
+            .arg("--paired")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let expected = "geneA\t1\ngeneB\t1\ngeneC\t0\ngeneD\t0\n";
+        assert_eq!(String::from_utf8(output.stdout)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_count_stranded() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let gtf = "tests/datasets/bam/count.gtf";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("count")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-g")
+            .arg(gtf)
+            .arg("-s")
+            .arg("m")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let expected = "geneA\t0\ngeneB\t2\ngeneC\t2\ngeneD\t0\n";
+        assert_eq!(String::from_utf8(output.stdout)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_coverage() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/coverage.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("coverage")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(get_num_lines(&output.stdout), 4);
+        assert_eq!(get_num_cols(&output.stdout), 7);
+        let stdout = String::from_utf8(output.stdout)?;
+        let second = stdout.lines().nth(1).unwrap();
+        assert!(second.ends_with("\t6"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_coverage_strand_counts() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/coverage.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("coverage")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .arg("--strand-counts")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(get_num_lines(&output.stdout), 4);
+        assert_eq!(get_num_cols(&output.stdout), 9);
+        let stdout = String::from_utf8(output.stdout)?;
+        let second = stdout.lines().nth(1).unwrap();
+        assert!(second.ends_with("\t6\t4\t2"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_coverage_breadth() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/coverage.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("coverage")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .arg("--breadth")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(get_num_lines(&output.stdout), 4);
+        assert_eq!(get_num_cols(&output.stdout), 10);
+        let stdout = String::from_utf8(output.stdout)?;
+        let second = stdout.lines().nth(1).unwrap();
+        assert!(second.ends_with("\t6\t96\t200\t0.48"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_coverage_breadth_mean_depth() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/coverage.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("coverage")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .arg("--breadth")
+            .arg("--mean-depth")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(get_num_lines(&output.stdout), 4);
+        assert_eq!(get_num_cols(&output.stdout), 11);
+        let stdout = String::from_utf8(output.stdout)?;
+        let second = stdout.lines().nth(1).unwrap();
+        assert!(second.ends_with("\t6\t96\t200\t0.48\t1.325"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_coverage_mean_depth() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/coverage.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("coverage")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .arg("--mean-depth")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(get_num_lines(&output.stdout), 4);
+        assert_eq!(get_num_cols(&output.stdout), 8);
+        let stdout = String::from_utf8(output.stdout)?;
+        let second = stdout.lines().nth(1).unwrap();
+        assert!(second.ends_with("\t6\t1.325"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_filter() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/filter.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("filter")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .arg("-O")
+            .arg("sam") // show SAM output for easier time counting lines
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        let num_header_lines = get_num_header_lines(&output.stdout);
+        assert_eq!(num_lines, 215);
+        assert_eq!(num_header_lines, 200);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_filter_invert() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/filter.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("filter")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .arg("-O")
+            .arg("sam") // show SAM output for easier time counting lines
+            .arg("-v")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
         let num_header_lines = get_num_header_lines(&output.stdout);
         assert_eq!(num_lines, 285);
         assert_eq!(num_header_lines, 200);
         Ok(())
     }
+
+    #[test]
+    fn test_bam_filter_complement() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/filter.bed";
+        let complement = "tiny.complement.sam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("filter")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .arg("-u")
+            .arg(complement)
+            .arg("-O")
+            .arg("sam")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let complement_output = std::fs::read(complement)?;
+        std::fs::remove_file(complement)?;
+        assert_eq!(get_num_lines(&output.stdout), 215);
+        assert_eq!(get_num_header_lines(&complement_output), 200);
+        assert_eq!(get_num_lines(&complement_output), 285);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_filter_split() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/filter.bed";
+        let prefix = "tiny.split.";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("filter")
+            .arg("-a")
+            .arg(a_set)
+            .arg("-b")
+            .arg(b_set)
+            .arg("--split")
+            .arg(prefix)
+            .arg("-O")
+            .arg("sam")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        assert!(output.stdout.is_empty());
+        let mut num_files = 0;
+        let mut num_records = 0;
+        for entry in std::fs::read_dir(".")? {
+            let path = entry?.path();
+            let is_split = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(prefix) && name.ends_with(".sam"));
+            if is_split {
+                let split_output = std::fs::read(&path)?;
+                std::fs::remove_file(&path)?;
+                num_files += 1;
+                num_records +=
+                    get_num_lines(&split_output) - get_num_header_lines(&split_output) - 1;
+            }
+        }
+        assert_eq!(num_files, 10);
+        assert_eq!(num_records, 14);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_filter_indexed() -> Result<()> {
+        // the BAM index is used unless the records are streamed from stdin
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/span.bed";
+        let mut outputs = Vec::new();
+        for indexed in [true, false] {
+            let mut cmd = Command::cargo_bin("gia")?;
+            cmd.arg("bam").arg("filter").arg("-b").arg(b_set);
+            if indexed {
+                cmd.arg("-a").arg(a_set);
+            } else {
+                cmd.stdin(std::fs::File::open(a_set)?);
+            }
+            let output = cmd.arg("-O").arg("sam").output()?;
+            assert!(output.status.success());
+            assert_eq!(output.stderr, b"");
+            outputs.push(String::from_utf8(output.stdout)?);
+        }
+        assert_eq!(outputs[0], outputs[1]);
+        let records = outputs[0]
+            .lines()
+            .filter(|line| !line.starts_with('@'))
+            .map(|line| line.split('\t').nth(3).unwrap())
+            .collect::<Vec<_>>();
+        // records starting before a region are found, and a spliced record
+        // spanning two regions is only reported once
+        assert_eq!(records, ["49862732", "49862732", "32487736", "24953752"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_coverage_indexed() -> Result<()> {
+        let a_set = "tests/datasets/bam/tiny.bam";
+        let b_set = "tests/datasets/bam/span.bed";
+        let mut outputs = Vec::new();
+        for indexed in [true, false] {
+            let mut cmd = Command::cargo_bin("gia")?;
+            cmd.arg("bam").arg("coverage").arg("-b").arg(b_set);
+            if indexed {
+                cmd.arg("-a").arg(a_set);
+            } else {
+                cmd.stdin(std::fs::File::open(a_set)?);
+            }
+            let output = cmd.output()?;
+            assert!(output.status.success());
+            assert_eq!(output.stderr, b"");
+            outputs.push(String::from_utf8(output.stdout)?);
+        }
+        assert_eq!(outputs[0], outputs[1]);
+        let expected = "8\t24953760\t24953800\t1\n8\t24953900\t24954000\t1\n14\t49862750\t49862800\t2\n17\t32487700\t32487740\t1\n";
+        assert_eq!(outputs[0], expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_genomecov() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("genomecov")
+            .arg("-i")
+            .arg(input)
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        let num_cols = get_num_cols(&output.stdout);
+        assert_eq!(num_lines, 111);
+        assert_eq!(num_cols, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_genomecov_cpm() -> Result<()> {
+        // files are counted in a first pass while stdin is buffered
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut outputs = Vec::new();
+        for from_file in [true, false] {
+            let mut cmd = Command::cargo_bin("gia")?;
+            cmd.arg("bam").arg("genomecov").arg("--cpm");
+            if from_file {
+                cmd.arg("-i").arg(input);
+            } else {
+                cmd.stdin(std::fs::File::open(input)?);
+            }
+            let output = cmd.output()?;
+            assert!(output.status.success());
+            assert_eq!(output.stderr, b"");
+            outputs.push(String::from_utf8(output.stdout)?);
+        }
+        assert_eq!(outputs[0], outputs[1]);
+        assert_eq!(
+            outputs[0].lines().next(),
+            Some("1\t155322848\t155322913\t10204.081632653062")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_genomecov_split() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("genomecov")
+            .arg("-i")
+            .arg(input)
+            .arg("--split")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        let num_cols = get_num_cols(&output.stdout);
+        assert_eq!(num_lines, 122);
+        assert_eq!(num_cols, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_genomecov_zero() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("genomecov")
+            .arg("-i")
+            .arg(input)
+            .arg("--zero")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        let num_cols = get_num_cols(&output.stdout);
+        assert_eq!(num_lines, 385);
+        assert_eq!(num_cols, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_stats_genome() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd.arg("bam").arg("stats").arg("-i").arg(input).output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        assert_eq!(get_num_lines(&output.stdout), 2);
+        assert_eq!(get_num_cols(&output.stdout), 18);
+        let stdout = String::from_utf8(output.stdout)?;
+        let fields = stdout.trim_end().split('\t').collect::<Vec<_>>();
+        assert_eq!(fields[0], "genome");
+        assert_eq!(fields[3], "98");
+        assert_eq!(fields[4], "44");
+        assert_eq!(fields[10..], ["0", "14", "0", "0", "0", "0", "0", "84"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bam_stats_regions() -> Result<()> {
+        let input = "tests/datasets/bam/tiny.bam";
+        let bed = "tests/datasets/bam/filter.bed";
+        let json = "tiny.stats.json";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bam")
+            .arg("stats")
+            .arg("-i")
+            .arg(input)
+            .arg("-b")
+            .arg(bed)
+            .arg("--json")
+            .arg(json)
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let json_output = std::fs::read_to_string(json)?;
+        std::fs::remove_file(json)?;
+        assert_eq!(get_num_lines(&output.stdout), 11);
+        assert_eq!(get_num_cols(&output.stdout), 21);
+        assert!(json_output.starts_with('['));
+        assert_eq!(json_output.matches("\"reads\":").count(), 10);
+        Ok(())
+    }
 }
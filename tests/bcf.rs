@@ -1,5 +1,8 @@
+mod common;
+
 #[cfg(test)]
 mod testing {
+    use crate::common::run_gia;
     use anyhow::Result;
     use assert_cmd::prelude::*;
     use std::process::Command;
@@ -17,6 +20,13 @@ mod testing {
             .count()
     }
 
+    fn get_num_records(output: &[u8]) -> usize {
+        output
+            .split(|&c| c == b'\n')
+            .filter(|line| !line.is_empty() && !line.starts_with(b"#"))
+            .count()
+    }
+
     fn get_num_header_lines(output: &[u8]) -> usize {
         output
             .split(|&c| c == b'\n')
@@ -139,4 +149,482 @@ mod testing {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_bcf_convert() -> Result<()> {
+        let input = "tests/datasets/bcf/chr22.bcf";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_lines = get_num_lines(&output.stdout);
+        let num_cols = get_num_cols(&output.stdout);
+        assert_eq!(num_lines, 10377);
+        assert_eq!(num_cols, 6);
+
+        let stdout = String::from_utf8(output.stdout)?;
+        let mut lines = stdout.lines();
+        assert_eq!(
+            lines.next(),
+            Some("22\t50300077\t50300078\trs7410291\tA\tG")
+        );
+
+        // records without an ID are named by their position and alleles
+        assert!(stdout
+            .lines()
+            .any(|line| line == "22\t50301487\t50301488\t22:50301488:C:T\tC\tT"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_convert_info_genotypes() -> Result<()> {
+        let input = "tests/datasets/bcf/chr22.bcf";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("-I")
+            .arg("AF,VT,SVTYPE")
+            .arg("-s")
+            .arg("HG00097")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stderr, b"");
+        let num_cols = get_num_cols(&output.stdout);
+        assert_eq!(num_cols, 10);
+
+        let stdout = String::from_utf8(output.stdout)?;
+        let first = stdout.lines().next().unwrap();
+        assert!(first.ends_with("\t0.34\tSNP\t.\t0|0"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_convert_all_genotypes() -> Result<()> {
+        let input = "tests/datasets/bcf/chr22.bcf";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("--genotypes")
+            .output()?;
+        assert!(output.status.success());
+        let num_cols = get_num_cols(&output.stdout);
+        assert_eq!(num_cols, 11);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_convert_missing_sample() -> Result<()> {
+        let input = "tests/datasets/bcf/chr22.bcf";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("convert")
+            .arg("-i")
+            .arg(input)
+            .arg("-s")
+            .arg("NA12878")
+            .output()?;
+        assert!(!output.status.success());
+        Ok(())
+    }
+
+    fn run_sv_filter(args: &[&str]) -> Result<usize> {
+        let base = [
+            "bcf",
+            "filter",
+            "-a",
+            "tests/datasets/bcf/sv.vcf",
+            "-b",
+            "tests/datasets/bcf/sv.bed",
+            "-O",
+            "v",
+        ];
+        let output = run_gia(&[&base[..], args].concat())?;
+        assert_eq!(output.stderr, b"");
+        Ok(get_num_records(&output.stdout))
+    }
+
+    #[test]
+    fn test_bcf_filter_sv_spans() -> Result<()> {
+        // the SVLEN deletion, the breakend anchored in the region, and its mate
+        assert_eq!(run_sv_filter(&[])?, 3);
+        assert_eq!(run_sv_filter(&["--ignore-mates"])?, 2);
+        // the END deletion only reaches the region with its confidence interval
+        assert_eq!(run_sv_filter(&["--ci-padding"])?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_convert_sv_spans() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("convert")
+            .arg("-i")
+            .arg("tests/datasets/bcf/sv.vcf")
+            .output()?;
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout)?;
+        let lines = stdout.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "1\t999\t2000\tdel_end\tN\t<DEL>");
+        assert_eq!(lines[1], "1\t4999\t5500\tdel_svlen\tN\t<DEL>");
+        assert_eq!(lines[2], "1\t7999\t8000\tins\tN\t<INS>");
+        assert_eq!(lines[3], "1\t9999\t10000\tbnd_a\tN\tN[2:3000[");
+        assert_eq!(lines[4], "2\t2999\t3000\tbnd_a\tN\tN[2:3000[");
+
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("convert")
+            .arg("-i")
+            .arg("tests/datasets/bcf/sv.vcf")
+            .arg("--ci-padding")
+            .arg("--ignore-mates")
+            .output()?;
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout)?;
+        let lines = stdout.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "1\t989\t2020\tdel_end\tN\t<DEL>");
+        assert_eq!(lines[2], "1\t7949\t8050\tins\tN\t<INS>");
+        Ok(())
+    }
+
+    fn run_annotate(args: &[&str]) -> Result<String> {
+        let base = [
+            "bcf",
+            "annotate",
+            "-a",
+            "tests/datasets/bcf/chr22.bcf",
+            "-b",
+            "tests/datasets/bcf/annotate.bed",
+            "-O",
+            "v",
+        ];
+        let output = run_gia(&[&base[..], args].concat())?;
+        assert_eq!(output.stderr, b"");
+        assert_eq!(get_num_header_lines(&output.stdout), 33);
+        assert_eq!(get_num_records(&output.stdout), 10376);
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    #[test]
+    fn test_bcf_annotate_names() -> Result<()> {
+        let stdout = run_annotate(&[])?;
+        assert!(stdout.contains("##INFO=<ID=BED,Number=.,Type=String,"));
+        assert_eq!(stdout.matches(";BED=").count(), 1696);
+        assert_eq!(stdout.matches(";BED=geneA,geneB\t").count(), 95);
+        // overlapping intervals with the same name are only reported once
+        assert_eq!(stdout.matches(";BED=geneC\t").count(), 1250);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_annotate_scores() -> Result<()> {
+        let stdout = run_annotate(&["--mode", "score", "--key", "SCORE"])?;
+        assert!(stdout.contains("##INFO=<ID=SCORE,Number=.,Type=Float,"));
+        assert_eq!(stdout.matches(";SCORE=").count(), 1696);
+        assert_eq!(stdout.matches(";SCORE=5\t").count(), 99);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_annotate_flag() -> Result<()> {
+        let stdout = run_annotate(&["--mode", "flag"])?;
+        assert!(stdout.contains("##INFO=<ID=BED,Number=0,Type=Flag,"));
+        assert_eq!(stdout.matches(";BED\t").count(), 1696);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_annotate_existing_key() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("annotate")
+            .arg("-a")
+            .arg("tests/datasets/bcf/chr22.bcf")
+            .arg("-b")
+            .arg("tests/datasets/bcf/annotate.bed")
+            .arg("--key")
+            .arg("AF")
+            .output()?;
+        assert!(!output.status.success());
+
+        // the type matches but the number of values does not
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("annotate")
+            .arg("-a")
+            .arg("tests/datasets/bcf/chr22.bcf")
+            .arg("-b")
+            .arg("tests/datasets/bcf/annotate.bed")
+            .arg("--key")
+            .arg("SVTYPE")
+            .output()?;
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)?.contains("Number=1 and Type=String"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_annotate_flag_bed3() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("annotate")
+            .arg("-a")
+            .arg("tests/datasets/bcf/chr22.bcf")
+            .arg("-b")
+            .arg("tests/datasets/bcf/annotate.bed3")
+            .arg("--mode")
+            .arg("flag")
+            .arg("-O")
+            .arg("v")
+            .output()?;
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout)?;
+        assert_eq!(stdout.matches(";BED\t").count(), 1696);
+
+        // BED3 intervals have no names to annotate with
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("annotate")
+            .arg("-a")
+            .arg("tests/datasets/bcf/chr22.bcf")
+            .arg("-b")
+            .arg("tests/datasets/bcf/annotate.bed3")
+            .output()?;
+        assert!(!output.status.success());
+        Ok(())
+    }
+
+    fn run_bcf_coverage(args: &[&str]) -> Result<Vec<String>> {
+        let base = [
+            "bcf",
+            "coverage",
+            "-a",
+            "tests/datasets/bcf/chr22.bcf",
+            "-b",
+            "tests/datasets/bcf/annotate.bed",
+        ];
+        let output = run_gia(&[&base[..], args].concat())?;
+        assert_eq!(output.stderr, b"");
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(stdout.lines().map(|line| line.to_string()).collect())
+    }
+
+    #[test]
+    fn test_bcf_coverage() -> Result<()> {
+        let lines = run_bcf_coverage(&[])?;
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("22\t50300000\t50310000\tgeneA"));
+        assert!(lines[0].ends_with("\t194"));
+        assert!(lines[1].ends_with("\t347"));
+        assert!(lines[2].ends_with("\t1250"));
+        assert!(lines[3].ends_with("\t124"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_coverage_by_type() -> Result<()> {
+        let lines = run_bcf_coverage(&["--by-type"])?;
+        assert!(lines[0].ends_with("\t194\t189\t5\t0"));
+        assert!(lines[2].ends_with("\t1250\t1197\t52\t1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_coverage_sample() -> Result<()> {
+        let lines = run_bcf_coverage(&["--sample", "HG00097"])?;
+        assert!(lines[0].ends_with("\t194\t1\t193"));
+        assert!(lines[1].ends_with("\t347\t69\t278"));
+
+        let lines = run_bcf_coverage(&["--by-type", "--sample", "HG00097"])?;
+        assert!(lines[2].ends_with("\t1250\t1197\t52\t1\t159\t1091"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_filter_indexed() -> Result<()> {
+        // the compressed VCF gives the same records with and without its tabix index
+        let a_set = "tests/datasets/bcf/chr22.vcf.gz";
+        let b_set = "tests/datasets/bcf/filter.bed";
+        let mut outputs = Vec::new();
+        for args in [vec![], vec!["--no-index"]] {
+            let mut cmd = Command::cargo_bin("gia")?;
+            let output = cmd
+                .arg("bcf")
+                .arg("filter")
+                .arg("-a")
+                .arg(a_set)
+                .arg("-b")
+                .arg(b_set)
+                .arg("-O")
+                .arg("v")
+                .args(args)
+                .output()?;
+            assert!(output.status.success());
+            assert_eq!(output.stderr, b"");
+            assert_eq!(get_num_records(&output.stdout), 1004);
+            outputs.push(output.stdout);
+        }
+        assert_eq!(outputs[0], outputs[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_filter_indexed_mates() -> Result<()> {
+        // the mate of bnd_a is in the region but its anchor is not, so it is only
+        // found if the indexed file is streamed
+        let b_set = "tests/datasets/bcf/sv_mate.bed";
+        for a_set in ["tests/datasets/bcf/sv.vcf", "tests/datasets/bcf/sv.vcf.gz"] {
+            let output = run_gia(&["bcf", "filter", "-a", a_set, "-b", b_set, "-O", "v"])?;
+            let stdout = String::from_utf8(output.stdout)?;
+            let ids = stdout
+                .lines()
+                .filter(|line| !line.starts_with('#'))
+                .filter_map(|line| line.split('\t').nth(2))
+                .collect::<Vec<_>>();
+            assert_eq!(ids, vec!["bnd_a", "bnd_b"]);
+        }
+        Ok(())
+    }
+
+    fn run_sample_filter(args: &[&str]) -> Result<Vec<u8>> {
+        let base = [
+            "bcf",
+            "filter",
+            "-a",
+            "tests/datasets/bcf/chr22.bcf",
+            "-b",
+            "tests/datasets/bcf/filter.bed",
+            "-O",
+            "v",
+        ];
+        let output = run_gia(&[&base[..], args].concat())?;
+        assert_eq!(output.stderr, b"");
+        Ok(output.stdout)
+    }
+
+    #[test]
+    fn test_bcf_filter_samples() -> Result<()> {
+        let stdout = run_sample_filter(&["--samples", "HG00096,HG00097"])?;
+        assert_eq!(get_num_records(&stdout), 1004);
+        assert_eq!(get_num_cols(&stdout), 11);
+        let header = String::from_utf8(stdout)?;
+        assert!(header.contains("\tFORMAT\tHG00096\tHG00097\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_filter_min_carriers() -> Result<()> {
+        let stdout = run_sample_filter(&["--samples", "HG00096,HG00097", "--min-carriers", "1"])?;
+        assert_eq!(get_num_records(&stdout), 155);
+        assert_eq!(get_num_cols(&stdout), 11);
+
+        // all samples are checked if none are selected
+        let stdout = run_sample_filter(&["--min-carriers", "2"])?;
+        assert_eq!(get_num_records(&stdout), 132);
+        assert_eq!(get_num_cols(&stdout), 14);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_filter_missing_sample() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("filter")
+            .arg("-a")
+            .arg("tests/datasets/bcf/chr22.bcf")
+            .arg("-b")
+            .arg("tests/datasets/bcf/filter.bed")
+            .arg("--samples")
+            .arg("NA12878")
+            .output()?;
+        assert!(!output.status.success());
+        Ok(())
+    }
+
+    fn run_intersect(args: &[&str]) -> Result<usize> {
+        let base = [
+            "bcf",
+            "intersect",
+            "-a",
+            "tests/datasets/bcf/sv.vcf",
+            "-b",
+            "tests/datasets/bcf/sv_truth.vcf",
+            "-O",
+            "v",
+        ];
+        let output = run_gia(&[&base[..], args].concat())?;
+        assert_eq!(output.stderr, b"");
+        Ok(get_num_records(&output.stdout))
+    }
+
+    #[test]
+    fn test_bcf_intersect_allele() -> Result<()> {
+        // only the SNV shares its REF and ALT alleles
+        assert_eq!(run_intersect(&[])?, 1);
+        assert_eq!(run_intersect(&["-R", "a-only"])?, 5);
+        assert_eq!(run_intersect(&["-R", "b-only"])?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_intersect_position() -> Result<()> {
+        // the SNV matches both records at its position
+        assert_eq!(run_intersect(&["-m", "position"])?, 1);
+        assert_eq!(run_intersect(&["-m", "position", "-R", "b-only"])?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_intersect_overlap() -> Result<()> {
+        assert_eq!(run_intersect(&["-m", "overlap"])?, 3);
+        assert_eq!(run_intersect(&["-m", "overlap", "-R", "b-only"])?, 1);
+
+        // the shifted deletion only shares a fifth of its span
+        assert_eq!(run_intersect(&["-m", "overlap", "-f", "0.5", "-r"])?, 2);
+        assert_eq!(
+            run_intersect(&["-m", "overlap", "-f", "0.5", "-r", "-R", "b-only"])?,
+            2
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_intersect_self() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("bcf")
+            .arg("intersect")
+            .arg("-a")
+            .arg("tests/datasets/bcf/chr22.bcf")
+            .arg("-b")
+            .arg("tests/datasets/bcf/chr22.vcf.gz")
+            .arg("-R")
+            .arg("a-only")
+            .arg("-O")
+            .arg("v")
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(get_num_records(&output.stdout), 0);
+        Ok(())
+    }
 }
@@ -0,0 +1,17 @@
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use std::process::{Command, Output};
+
+/// Runs gia with the arguments and checks that it succeeded
+///
+/// The stderr of a failed run is included in the panic message.
+pub fn run_gia(args: &[&str]) -> Result<Output> {
+    let output = Command::cargo_bin("gia")?.args(args).output()?;
+    assert!(
+        output.status.success(),
+        "gia {} failed:\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(output)
+}
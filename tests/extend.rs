@@ -145,4 +145,20 @@ mod testing {
         assert_eq!(output.stdout, expected_str.as_bytes());
         Ok(())
     }
+
+    #[test]
+    fn test_extend_bed3_plus() -> Result<()> {
+        let input = "tests/datasets/io/bed3_plus.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("extend")
+            .arg("-i")
+            .arg(input)
+            .arg("-l")
+            .arg("5")
+            .output()?;
+        let expected = "1\t25\t40\t7\t8\n2\t0\t15\t1\t2\n1\t5\t20\t8\t9\n";
+        assert_eq!(output.stdout, expected.as_bytes());
+        Ok(())
+    }
 }
@@ -0,0 +1,94 @@
+mod common;
+
+#[cfg(test)]
+mod testing {
+    use crate::common::run_gia;
+    use anyhow::Result;
+    use assert_cmd::prelude::*;
+    use std::process::Command;
+
+    const GFF: &str = "tests/datasets/gff/genes.gff3";
+
+    fn run_convert(args: &[&str]) -> Result<String> {
+        let base = ["gff", "convert", "-i", GFF];
+        let output = run_gia(&[&base[..], args].concat())?;
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    #[test]
+    fn test_gff_convert() -> Result<()> {
+        let output = run_convert(&[])?;
+        let expected = [
+            "chr1\t1000\t2000\tgene1\t.\t+",
+            "chr1\t1000\t2000\ttx1\t.\t+",
+            "chr1\t1000\t1200\texon1\t.\t+",
+            "chr1\t1800\t2000\texon2\t.\t+",
+            "chr1\t1100\t1200\tcds1\t.\t+",
+            "chr1\t1800\t1900\tcds1\t.\t+",
+            "chr2\t500\t900\tgene2\t10\t-",
+            "chr2\t500\t900\ttx2\t.\t-",
+            // features without an ID are named by their parent
+            "chr2\t500\t900\ttx2\t.\t-",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gff_convert_feature_name() -> Result<()> {
+        let output = run_convert(&["-f", "exon", "-n", "Name"])?;
+        let expected = [
+            "chr1\t1000\t1200\tAlpha-201\t.\t+",
+            "chr1\t1800\t2000\tAlpha-201\t.\t+",
+            "chr2\t500\t900\tGamma-201\t.\t-",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gff_convert_ancestor() -> Result<()> {
+        let output = run_convert(&["-f", "mRNA,exon", "-n", "Name", "-a", "gene"])?;
+        let expected = [
+            // the name is URL-decoded
+            "chr1\t1000\t2000\tAlpha;Beta\t.\t+",
+            "chr1\t1000\t1200\tAlpha;Beta\t.\t+",
+            "chr1\t1800\t2000\tAlpha;Beta\t.\t+",
+            // the gene has no name so the mRNA name is not used
+            "chr2\t500\t900\t.\t.\t-",
+            "chr2\t500\t900\t.\t.\t-",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gff_convert_missing() -> Result<()> {
+        let output = run_convert(&["-f", "CDS", "-n", "gene_biotype", "--missing", "NA"])?;
+        let expected = [
+            "chr1\t1100\t1200\tprotein_coding\t.\t+",
+            "chr1\t1800\t1900\tprotein_coding\t.\t+",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+
+        let output = run_convert(&["-f", "gene", "-n", "Name", "--missing", "NA"])?;
+        let expected = [
+            "chr1\t1000\t2000\tAlpha;Beta\t.\t+",
+            "chr2\t500\t900\tNA\t10\t-",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gff_convert_requires_gff() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        cmd.arg("gff")
+            .arg("convert")
+            .arg("-i")
+            .arg("tests/datasets/io/bed3_plus.bed")
+            .assert()
+            .failure();
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+mod common;
+
+#[cfg(test)]
+mod testing {
+    use crate::common::run_gia;
+    use anyhow::Result;
+    use assert_cmd::prelude::*;
+    use std::process::Command;
+
+    const GTF: &str = "tests/datasets/gtf/genes.gtf";
+
+    fn run_gtf(subcommand: &str, args: &[&str]) -> Result<String> {
+        let base = ["gtf", subcommand, "-i", GTF];
+        let output = run_gia(&[&base[..], args].concat())?;
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    #[test]
+    fn test_gtf_filter_bed4() -> Result<()> {
+        let args = [
+            "-f",
+            "exon",
+            "-w",
+            "gene_biotype == protein_coding",
+            "-n",
+            "gene_name",
+            "-O",
+            "bed4",
+        ];
+        let output = run_gtf("filter", &args)?;
+        let expected = [
+            "chr1\t1000\t1500\tAlpha",
+            "chr1\t2000\t2500\tAlpha",
+            "chr1\t4000\t5000\tAlpha",
+            "chr2\t3600\t4000\tGamma",
+            "chr2\t3000\t3400\tGamma",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_filter_bed6() -> Result<()> {
+        let output = run_gtf("filter", &["-f", "gene", "-n", "gene_name", "-O", "bed6"])?;
+        let expected = [
+            "chr1\t1000\t5000\tAlpha\t.\t+",
+            // features without the attribute are named `.`
+            "chr2\t100\t1000\t.\t.\t-",
+            "chr2\t3000\t4000\tGamma\t.\t-",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_filter_default_name() -> Result<()> {
+        let output = run_gtf(
+            "filter",
+            &["-f", "transcript,CDS", "-w", "gene_name", "-O", "bed4"],
+        )?;
+        let names = output
+            .lines()
+            .map(|line| line.split('\t').nth(3).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["g1", "g1", "g1", "g1", "g3", "g3", "g3"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_filter_gtf() -> Result<()> {
+        let output = run_gtf("filter", &["-w", "gene_biotype!=protein_coding"])?;
+        let expected = std::fs::read_to_string(GTF)?
+            .lines()
+            .filter(|line| line.contains("lncRNA"))
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(expected.len(), 4);
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_filter_invalid_predicate() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        cmd.arg("gtf")
+            .arg("filter")
+            .arg("-i")
+            .arg(GTF)
+            .arg("-w")
+            .arg("==protein_coding")
+            .assert()
+            .failure();
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_transcripts() -> Result<()> {
+        let output = run_gtf("transcripts", &[])?;
+        let expected = [
+            "chr1\t1000\t5000\tt1\t0\t+\t1200\t4303\t0\t3\t500,500,1000\t0,1000,3000",
+            // non-coding transcripts have an empty thick span
+            "chr2\t100\t1000\tt2\t0\t-\t100\t100\t0\t2\t200,300\t0,600",
+            "chr2\t3000\t4000\tt3\t0\t-\t3197\t3800\t0\t2\t400,400\t0,600",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_transcripts_selection() -> Result<()> {
+        let args = ["-w", "gene_biotype==protein_coding", "-n", "gene_name"];
+        let output = run_gtf("transcripts", &args)?;
+        let names = output
+            .lines()
+            .map(|line| line.split('\t').nth(3).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Alpha", "Gamma"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_introns() -> Result<()> {
+        let output = run_gtf("introns", &[])?;
+        let expected = [
+            "chr1\t1500\t2000\tt1\t0\t+",
+            "chr1\t2500\t4000\tt1\t0\t+",
+            "chr2\t300\t700\tt2\t0\t-",
+            "chr2\t3400\t3600\tt3\t0\t-",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_utrs() -> Result<()> {
+        // the UTRs of reverse strand transcripts are swapped in genome order
+        let output = run_gtf("utrs", &["-e", "five"])?;
+        let expected = ["chr1\t1000\t1200\tt1\t0\t+", "chr2\t3800\t4000\tt3\t0\t-"];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+
+        // the stop codons are not part of the 3' UTRs
+        let output = run_gtf("utrs", &["-e", "three"])?;
+        let expected = ["chr1\t4303\t5000\tt1\t0\t+", "chr2\t3000\t3197\tt3\t0\t-"];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_tss_tes() -> Result<()> {
+        let output = run_gtf("tss", &[])?;
+        let expected = [
+            "chr1\t1000\t1001\tt1\t0\t+",
+            "chr2\t999\t1000\tt2\t0\t-",
+            "chr2\t3999\t4000\tt3\t0\t-",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+
+        let output = run_gtf("tes", &[])?;
+        let expected = [
+            "chr1\t4999\t5000\tt1\t0\t+",
+            "chr2\t100\t101\tt2\t0\t-",
+            "chr2\t3000\t3001\tt3\t0\t-",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_promoters() -> Result<()> {
+        let output = run_gtf("promoters", &["-l", "500", "-r", "100"])?;
+        let expected = [
+            "chr1\t500\t1101\tt1\t0\t+",
+            // upstream of reverse strand transcripts is to the right
+            "chr2\t899\t1500\tt2\t0\t-",
+            "chr2\t3899\t4500\tt3\t0\t-",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gtf_promoters_bounds() -> Result<()> {
+        let genome = "tests/datasets/gtf/genes.genome";
+        let output = run_gtf("promoters", &["-t", "2000", "-g", genome])?;
+        let expected = [
+            "chr1\t0\t3001\tt1\t0\t+",
+            "chr2\t0\t3000\tt2\t0\t-",
+            "chr2\t1999\t4200\tt3\t0\t-",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+}
@@ -305,4 +305,32 @@ mod testing {
         assert_eq!(output.stdout, expected_str.as_bytes());
         Ok(())
     }
+
+    #[test]
+    fn test_intersect_with_query_bed3_plus() -> Result<()> {
+        let a = "tests/datasets/io/bed3_plus.bed";
+        let b = "tests/datasets/extend/extend.bed";
+
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("intersect")
+            .arg("-a")
+            .arg(a)
+            .arg("-b")
+            .arg(b)
+            .arg("-q")
+            .output()?;
+        let output_str = String::from_utf8(output.stdout)?;
+        let rows = output_str
+            .lines()
+            .map(|row| row.split('\t').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        // the payload of each query record is carried through
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.len() == 5));
+        assert!(rows.contains(&vec!["1", "10", "20", "8", "9"]));
+        assert!(rows.contains(&vec!["1", "30", "40", "7", "8"]));
+        Ok(())
+    }
 }
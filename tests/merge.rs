@@ -253,4 +253,22 @@ mod testing {
         assert_eq!(output.stdout, expected_str.as_bytes());
         Ok(())
     }
+
+    #[test]
+    fn test_merge_stream_bed3_plus() -> Result<()> {
+        let input = "tests/datasets/merge/sorted.bed3_plus";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("merge")
+            .arg("-S")
+            .arg("-i")
+            .arg(input)
+            .arg("--demote")
+            .output()?;
+
+        let expected = vec![(1, 10, 30), (2, 1, 5)];
+        let expected_str = build_expected_str(&expected);
+        assert_eq!(output.stdout, expected_str.as_bytes());
+        Ok(())
+    }
 }
@@ -0,0 +1,159 @@
+mod common;
+
+#[cfg(test)]
+mod testing {
+    use crate::common::run_gia;
+    use anyhow::Result;
+    use assert_cmd::prelude::*;
+    use std::process::Command;
+
+    const NARROW_PEAK: &str = "tests/datasets/peak/peaks.narrowPeak";
+    const BROAD_PEAK: &str = "tests/datasets/peak/peaks.broadPeak";
+
+    fn run_peak(subcommand: &str, input: &str, args: &[&str]) -> Result<String> {
+        let base = ["peak", subcommand, "-i", input];
+        let output = run_gia(&[&base[..], args].concat())?;
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    fn get_names(output: &str) -> Vec<&str> {
+        output
+            .lines()
+            .map(|line| line.split('\t').nth(3).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_peak_summit() -> Result<()> {
+        let output = run_peak("summit", NARROW_PEAK, &["-e", "10"])?;
+        let expected = [
+            "chr1\t130\t151\tpeak_1\t50\t.\t4.5\t25\t8.1\t10",
+            "chr1\t200\t221\tpeak_2\t80\t.\t9\t20.5\t15.3\t10",
+            // peaks without a summit are centred on their midpoint
+            "chr1\t270\t291\tpeak_3\t30\t.\t2\t5.1\t3.2\t10",
+            "chr1\t540\t561\tpeak_4\t90\t.\t7.5\t30\t25.7\t10",
+            "chr2\t20\t41\tpeak_5\t60\t.\t6\t12\t9.9\t10",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_peak_summit_genome() -> Result<()> {
+        // the re-centred peak_5 runs past the end of chr2 without the genome file
+        let args = ["-e", "10", "-g", "tests/datasets/peak/peaks.genome"];
+        let output = run_peak("summit", NARROW_PEAK, &args)?;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "chr1\t130\t151\tpeak_1\t50\t.\t4.5\t25\t8.1\t10");
+        assert_eq!(lines[4], "chr2\t20\t35\tpeak_5\t60\t.\t6\t12\t9.9\t10");
+        Ok(())
+    }
+
+    #[test]
+    fn test_peak_summit_broad_peak() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("peak")
+            .arg("summit")
+            .arg("-i")
+            .arg(BROAD_PEAK)
+            .output()?;
+        assert!(!output.status.success());
+        Ok(())
+    }
+
+    #[test]
+    fn test_peak_summit_outside_peak() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("peak")
+            .arg("summit")
+            .arg("-i")
+            .arg("tests/datasets/peak/invalid_summit.narrowPeak")
+            .output()?;
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)?.contains("outside of the peak"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_peak_merge() -> Result<()> {
+        let output = run_peak("merge", NARROW_PEAK, &[])?;
+        let expected = [
+            "chr1\t100\t300\tpeak_2\t80\t.\t9\t20.5\t15.3\t110",
+            "chr1\t500\t600\tpeak_4\t90\t.\t7.5\t30\t25.7\t50",
+            "chr2\t20\t80\tpeak_5\t60\t.\t6\t12\t9.9\t10",
+        ];
+        assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+
+        let output = run_peak("merge", NARROW_PEAK, &["-b", "p-value"])?;
+        assert_eq!(
+            output.lines().next(),
+            Some("chr1\t100\t300\tpeak_1\t50\t.\t4.5\t25\t8.1\t40")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_peak_merge_broad_peak() -> Result<()> {
+        let output = run_peak("merge", BROAD_PEAK, &[])?;
+        assert_eq!(
+            output.lines().next(),
+            Some("chr1\t100\t300\tpeak_2\t80\t.\t9\t20.5\t15.3")
+        );
+        assert_eq!(output.lines().count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_peak_sort() -> Result<()> {
+        let output = run_peak("sort", NARROW_PEAK, &[])?;
+        assert_eq!(
+            get_names(&output),
+            ["peak_4", "peak_2", "peak_5", "peak_1", "peak_3"]
+        );
+        let output = run_peak("sort", BROAD_PEAK, &["-b", "signal"])?;
+        assert_eq!(
+            get_names(&output),
+            ["peak_2", "peak_4", "peak_5", "peak_1", "peak_3"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_peak_requires_peak_input() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("peak")
+            .arg("sort")
+            .arg("-i")
+            .arg("tests/datasets/sort/unsorted.bed6")
+            .output()?;
+        assert!(!output.status.success());
+        Ok(())
+    }
+
+    #[test]
+    fn test_peak_rejects_bed9() -> Result<()> {
+        // the itemRgb of 0 and the integer thick coordinates are not peak columns
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd
+            .arg("peak")
+            .arg("sort")
+            .arg("-i")
+            .arg("tests/datasets/peak/item_rgb.bed9")
+            .output()?;
+        assert!(!output.status.success());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_keeps_peak_columns() -> Result<()> {
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd.arg("sort").arg("-i").arg(NARROW_PEAK).output()?;
+        let output = String::from_utf8(output.stdout)?;
+        assert_eq!(output.lines().count(), 5);
+        assert!(output.lines().all(|line| line.split('\t').count() == 10));
+        Ok(())
+    }
+}
@@ -361,4 +361,24 @@ mod testing {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_sort_bed3_plus() -> Result<()> {
+        let input = "tests/datasets/io/bed3_plus.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd.arg("sort").arg("-i").arg(input).output()?;
+        let expected = "1\t10\t20\t8\t9\n1\t30\t40\t7\t8\n2\t5\t15\t1\t2\n";
+        assert_eq!(output.stdout, expected.as_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_bed3_plus_named() -> Result<()> {
+        let input = "tests/datasets/io/bed3_plus_named.bed";
+        let mut cmd = Command::cargo_bin("gia")?;
+        let output = cmd.arg("sort").arg("-i").arg(input).output()?;
+        let expected = "chr1\t10\t20\tpeak_2\t7.1\tx\t4\nchr1\t30\t40\tpeak_1\t12.5\t.\t3\n";
+        assert_eq!(output.stdout, expected.as_bytes());
+        Ok(())
+    }
 }